@@ -0,0 +1,160 @@
+// Copyright Rob Gage 2025
+
+use crate::Expression;
+use num::{
+    bigint::BigInt,
+    Signed,
+    Zero,
+};
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    hash::Hash,
+};
+
+/// A property assumed of the values of a variable
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Assumption {
+
+    /// The variable is a real number greater than zero
+    Positive,
+
+    /// The variable is a real number
+    Real,
+
+    /// The variable is an integer
+    Integer,
+
+    /// The variable is not zero
+    NonZero,
+
+}
+
+/// The `Assumption`s made about variables, which permit rewrites of `Expression`s that are not
+/// valid for every value
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Assumptions<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The `Assumption`s made about each variable
+    variables: HashMap<I, HashSet<Assumption>>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Assumptions<I> {
+
+    /// Creates a new `Assumptions` with nothing assumed
+    pub fn new() -> Self { Self { variables: HashMap::new() } }
+
+    /// Assumes a property of a variable
+    pub fn assume(&mut self, variable: I, assumption: Assumption) {
+        self.variables.entry(variable).or_default().insert(assumption);
+    }
+
+    /// Returns `Assumptions` with a property of a variable also assumed
+    pub fn with(mut self, variable: I, assumption: Assumption) -> Self {
+        self.assume(variable, assumption);
+        self
+    }
+
+    /// Returns `true` if a property of a variable is assumed or implied by another assumption
+    pub fn holds(&self, variable: &I, assumption: Assumption) -> bool {
+        let Some (assumptions) = self.variables.get(variable) else { return false };
+        let assumed = |assumption: Assumption| assumptions.contains(&assumption);
+        match assumption {
+            Assumption::Positive => assumed(Assumption::Positive),
+            Assumption::Real => assumed(Assumption::Real)
+                || assumed(Assumption::Positive)
+                || assumed(Assumption::Integer),
+            Assumption::Integer => assumed(Assumption::Integer),
+            Assumption::NonZero => assumed(Assumption::NonZero) || assumed(Assumption::Positive),
+        }
+    }
+
+    /// Returns `true` if an `Expression` is known to be greater than zero
+    pub fn is_positive(&self, expression: &Expression<I>) -> bool {
+        use Expression::*;
+        match expression {
+            Sum (operands) | Product (operands) => !operands.is_empty() && operands.iter()
+                .all(|operand| self.is_positive(operand)),
+            Quotient (operands) => self.is_positive(&operands.0) && self.is_positive(&operands.1),
+            Power (operands) => self.is_positive(&operands.0) && self.is_real(&operands.1),
+            Exponential (operand) => self.is_real(operand),
+            Abs (operand) => self.is_nonzero(operand),
+            Sign (operand) => self.is_positive(operand),
+            Piecewise (branches) => !branches.is_empty() && branches.iter()
+                .all(|(_, branch)| self.is_positive(branch)),
+            Logarithm (_) | Function (..) | Derivative (..) => false,
+            Variable (variable) => self.holds(variable, Assumption::Positive),
+            Integer (integer) => integer.is_positive(),
+        }
+    }
+
+    /// Returns `true` if an `Expression` is known not to be zero
+    pub fn is_nonzero(&self, expression: &Expression<I>) -> bool {
+        use Expression::*;
+        if self.is_positive(expression) { return true }
+        match expression {
+            Sum (_) | Logarithm (_) | Function (..) | Derivative (..) => false,
+            Product (factors) => factors.iter().all(|factor| self.is_nonzero(factor)),
+            // quotients and powers must also be defined
+            Quotient (operands) => self.is_nonzero(&operands.0) && self.is_nonzero(&operands.1),
+            Power (operands) => self.is_nonzero(&operands.0)
+                && ((self.is_positive(&operands.0) && self.is_real(&operands.1))
+                    || self.is_integer(&operands.1)),
+            Exponential (operand) => self.is_real(operand),
+            Abs (operand) | Sign (operand) => self.is_nonzero(operand),
+            Piecewise (branches) => !branches.is_empty() && branches.iter()
+                .all(|(_, branch)| self.is_nonzero(branch)),
+            Variable (variable) => self.holds(variable, Assumption::NonZero),
+            Integer (integer) => !integer.is_zero(),
+        }
+    }
+
+    /// Returns `true` if an `Expression` is known to be a real number
+    pub fn is_real(&self, expression: &Expression<I>) -> bool {
+        use Expression::*;
+        match expression {
+            Sum (operands) | Product (operands) => operands.iter()
+                .all(|operand| self.is_real(operand)),
+            Quotient (operands) => self.is_real(&operands.0) && self.is_real(&operands.1)
+                && self.is_nonzero(&operands.1),
+            Power (operands) => self.is_real(&operands.1) && (self.is_positive(&operands.0)
+                || (self.is_real(&operands.0) && self.is_integer(&operands.1))),
+            Exponential (operand) => self.is_real(operand),
+            Logarithm (operand) => self.is_positive(operand),
+            Abs (_) => true,
+            Sign (operand) => self.is_real(operand),
+            Piecewise (branches) => branches.iter().all(|(_, branch)| self.is_real(branch)),
+            Function (..) | Derivative (..) => false,
+            Variable (variable) => self.holds(variable, Assumption::Real),
+            Integer (_) => true,
+        }
+    }
+
+    /// Returns `true` if an `Expression` is known to be an integer
+    pub fn is_integer(&self, expression: &Expression<I>) -> bool {
+        use Expression::*;
+        match expression {
+            Sum (operands) | Product (operands) => operands.iter()
+                .all(|operand| self.is_integer(operand)),
+            Power (operands) => self.is_integer(&operands.0) && matches!(
+                &operands.1,
+                Integer (exponent) if exponent >= &BigInt::ZERO
+            ),
+            Abs (operand) => self.is_integer(operand),
+            Sign (operand) => self.is_real(operand),
+            Piecewise (branches) => branches.iter().all(|(_, branch)| self.is_integer(branch)),
+            Quotient (_) | Exponential (_) | Logarithm (_) | Function (..) | Derivative (..) =>
+                false,
+            Variable (variable) => self.holds(variable, Assumption::Integer),
+            Integer (_) => true,
+        }
+    }
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Default for Assumptions<I> {
+    fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,209 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Condition,
+    Dag,
+    EvaluationError,
+    Expression,
+    Numeric,
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+};
+
+/// An identifier in an `Expression` with `Bindings`
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub enum Symbol<I: Clone + Eq + Hash + PartialEq = usize> {
+
+    /// A free variable, or the name of a function
+    Variable (I),
+
+    /// A reference to the value of an earlier binding
+    Binding (usize),
+
+}
+
+/// A list of `Expression`s whose variables may refer to bindings
+pub(crate) type Bound<I> = Vec<Expression<Symbol<I>>>;
+
+/// An `Expression` with its common subexpressions eliminated, as a sequence of let-bindings
+/// followed by a final expression
+///
+/// (Each binding may only refer to the bindings that come before it)
+pub struct Bindings<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The bound subexpressions
+    bindings: Vec<Expression<Symbol<I>>>,
+    /// The final expression
+    expression: Expression<Symbol<I>>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Bindings<I> {
+
+    /// Creates `Bindings` from a node in a `Dag`, binding every non-trivial node that is used
+    /// more than once
+    ///
+    /// (Integers, variables and quotients of integers are trivial)
+    pub fn from_dag(dag: &Dag<I>, root: usize) -> Self {
+        let (bindings, mut expressions) = Self::eliminate(dag, &[root], &[]);
+        Self { bindings, expression: expressions.remove(0) }
+    }
+
+    /// Binds every non-trivial node that is used more than once by a list of root nodes in a
+    /// `Dag`, or that is in a list of required nodes, and returns the bindings and the
+    /// expressions of the roots
+    ///
+    /// (A root that is also used by another root counts as used twice, as does a required node
+    /// that is used once)
+    pub(crate) fn eliminate(
+        dag: &Dag<I>,
+        roots: &[usize],
+        required: &[usize],
+    ) -> (Bound<I>, Bound<I>) {
+        let last: usize = roots.iter().copied().max().unwrap_or(0);
+        let mut reachable: Vec<bool> = vec![false; last + 1];
+        for root in roots {
+            for (index, reached) in dag.reachable(*root).into_iter().enumerate() {
+                reachable[index] |= reached;
+            }
+        }
+        // count the uses of each reachable node
+        let mut uses: Vec<usize> = vec![0; last + 1];
+        for (index, _) in reachable.iter().enumerate().filter(|(_, reachable)| **reachable) {
+            for operand in dag.operands(index) { uses[operand] += 1 }
+        }
+        for node in roots.iter().chain(required) { uses[*node] += 1 }
+        // bind shared nodes in topological order
+        let mut indices: HashMap<usize, usize> = HashMap::new();
+        let mut bindings: Bound<I> = Vec::new();
+        for index in 0..=last {
+            if reachable[index] && uses[index] > 1 && !Self::is_trivial(dag, index) {
+                bindings.push(Self::build(dag, index, &indices));
+                indices.insert(index, bindings.len() - 1);
+            }
+        }
+        let expressions: Bound<I> = roots.iter()
+            .map(|root| match indices.get(root) {
+                Some (index) => Expression::Variable (Symbol::Binding (*index)),
+                None => Self::build(dag, *root, &indices),
+            })
+            .collect();
+        (bindings, expressions)
+    }
+
+    /// Returns `true` if a node is not worth binding, because it is a variable or a number
+    fn is_trivial(dag: &Dag<I>, identifier: usize) -> bool {
+        use crate::Node;
+        match dag.node(identifier) {
+            Node::Quotient (dividend, divisor) => matches!(
+                (dag.node(*dividend), dag.node(*divisor)),
+                (Node::Integer (_), Node::Integer (_))
+            ),
+            node => matches!(node, Node::Variable (_) | Node::Integer (_)),
+        }
+    }
+
+    /// Builds the `Expression` of a node, referring to already bound nodes by their bindings
+    fn build(
+        dag: &Dag<I>,
+        identifier: usize,
+        indices: &HashMap<usize, usize>,
+    ) -> Expression<Symbol<I>> {
+        use crate::Node;
+        use Expression::*;
+        let operand = |operand: &usize| match indices.get(operand) {
+            Some (index) => Variable (Symbol::Binding (*index)),
+            None => Self::build(dag, *operand, indices),
+        };
+        match dag.node(identifier) {
+            Node::Sum (terms) => Sum (terms.iter().map(operand).collect()),
+            Node::Product (factors) => Product (factors.iter().map(operand).collect()),
+            Node::Quotient (dividend, divisor) =>
+                Quotient (Box::new((operand(dividend), operand(divisor)))),
+            Node::Power (base, exponent) =>
+                Power (Box::new((operand(base), operand(exponent)))),
+            Node::Exponential (argument) => Exponential (Box::new(operand(argument))),
+            Node::Logarithm (argument) => Logarithm (Box::new(operand(argument))),
+            Node::Abs (argument) => Abs (Box::new(operand(argument))),
+            Node::Sign (argument) => Sign (Box::new(operand(argument))),
+            Node::Piecewise (branches) => Piecewise (branches.iter()
+                .map(|(comparison, compared, branch)| {
+                    (Condition::new(*comparison, operand(compared)), operand(branch))
+                })
+                .collect()
+            ),
+            Node::Function (name, arguments) =>
+                Function (Symbol::Variable (name.clone()), arguments.iter().map(operand).collect()),
+            Node::Derivative (name, indices, arguments) => Derivative (
+                Symbol::Variable (name.clone()),
+                indices.clone(),
+                arguments.iter().map(operand).collect(),
+            ),
+            Node::Variable (name) => Variable (Symbol::Variable (name.clone())),
+            Node::Integer (integer) => Integer (integer.clone()),
+        }
+    }
+
+    /// Looks up the values of a `Symbol` from bound values and the values of a variable
+    fn lookup<'a, T>(
+        bound: &'a [Vec<T>],
+        symbol: &Symbol<I>,
+        variable: &I,
+        values: &'a [T],
+    ) -> Option<&'a [T]> {
+        match symbol {
+            Symbol::Variable (name) if name == variable => Some (values),
+            Symbol::Variable (_) => None,
+            Symbol::Binding (index) => bound.get(*index).map(Vec::as_slice),
+        }
+    }
+
+    /// Returns the bound subexpressions
+    pub fn bindings(&self) -> &[Expression<Symbol<I>>] { &self.bindings }
+
+    /// Returns the final expression
+    pub fn expression(&self) -> &Expression<Symbol<I>> { &self.expression }
+
+    /// Evaluates the `Bindings` with a list of `Numeric` input values for a given variable,
+    /// computing each binding only once
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Bindings`)
+    pub fn evaluate_with<T: Numeric>(
+        &self,
+        variable: &I,
+        values: &[T],
+    ) -> Result<Vec<T>, EvaluationError<I>> {
+        let mut bound: Vec<Vec<T>> = Vec::with_capacity(self.bindings.len());
+        for binding in self.bindings.iter() {
+            let output: Vec<T> = binding.evaluate_variables(
+                values,
+                &|symbol| Self::lookup(&bound, symbol, variable, values),
+            ).map_err(Self::unbound)?;
+            bound.push(output);
+        }
+        self.expression.evaluate_variables(
+            values,
+            &|symbol| Self::lookup(&bound, symbol, variable, values),
+        ).map_err(Self::unbound)
+    }
+
+    /// Returns the `EvaluationError` for a `Symbol` without values
+    fn unbound(symbol: Symbol<I>) -> EvaluationError<I> {
+        match symbol {
+            Symbol::Variable (name) => EvaluationError::UnboundVariable (name),
+            Symbol::Binding (_) => unreachable!("bindings only refer to earlier bindings"),
+        }
+    }
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Eliminates common subexpressions from an `Expression`, returning it as `Bindings`
+    pub fn eliminate_common_subexpressions(&self) -> Bindings<I> {
+        let mut dag: Dag<I> = Dag::new();
+        let root: usize = dag.insert(self);
+        Bindings::from_dag(&dag, root)
+    }
+
+}
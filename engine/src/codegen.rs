@@ -0,0 +1,521 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    notation::{
+        negation,
+        root_index,
+    },
+    Bindings,
+    Comparison,
+    Dag,
+    Expression,
+    Node,
+    Symbol,
+};
+use num::{
+    bigint::BigInt,
+    traits::ToPrimitive,
+};
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as FormatResult,
+        Write,
+    },
+    hash::Hash,
+};
+
+/// A programming language that source code is generated in
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Language {
+
+    /// Rust, with `f64` values
+    Rust,
+
+    /// C, with `double` values and the functions of `math.h`
+    C,
+
+    /// Python, with the functions of NumPy so that arrays can be passed as arguments
+    Python,
+
+    /// GLSL, with `float` values
+    Glsl,
+
+}
+
+/// An output of a generated function
+enum Output<I: Clone + Eq + Hash + PartialEq> {
+    /// The value of an `Expression`
+    Value (Expression<I>),
+    /// The value of an `Expression` followed by its partial derivatives with respect to each
+    /// parameter
+    Gradient (Expression<I>),
+}
+
+/// The largest magnitude of integer exponents of powers that are expanded into products in GLSL
+const EXPANDED: i32 = 4;
+
+/// The precedence of a generated subexpression, which determines whether it must be enclosed
+/// in parentheses to be used as an operand
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+enum Precedence {
+    /// A conditional expression
+    Conditional,
+    /// A sum or difference
+    Sum,
+    /// A negation
+    Negation,
+    /// A product or quotient
+    Product,
+    /// A power written with an operator
+    Power,
+    /// A literal, a name or a function call
+    Atom,
+}
+
+/// The source code of a function in a `Language` that computes the values of `Expression`s,
+/// with common subexpressions computed once and stored in local variables
+///
+/// (Local variables are named `t0`, `t1` and so on, so the parameters should not be, and
+/// applications of functions are generated as calls to functions of the same names, unless
+/// they are expanded first, with partial derivatives named like `f_d1_d2` by the 1-based
+/// indices of their arguments, while piecewise expressions become conditional expressions that
+/// are not a number where no branch is selected, and operands that are written more than once,
+/// such as the bases of powers expanded into products, are stored in local variables)
+pub struct Codegen<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The language of the generated function
+    language: Language,
+    /// The name of the generated function
+    name: String,
+    /// The parameters of the generated function, if not the free variables of its outputs
+    parameters: Option<Vec<I>>,
+    /// The outputs of the generated function
+    outputs: Vec<Output<I>>,
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Codegen<I> {
+
+    /// Creates a new `Codegen` for a function with a name and no outputs
+    pub fn new(language: Language, name: &str) -> Self {
+        Self { language, name: name.to_string(), parameters: None, outputs: Vec::new() }
+    }
+
+    /// Returns the `Codegen` with the value of an `Expression` added to the outputs
+    pub fn output(mut self, expression: Expression<I>) -> Self {
+        self.outputs.push(Output::Value (expression));
+        self
+    }
+
+    /// Returns the `Codegen` with the value of an `Expression` and its partial derivatives
+    /// with respect to each parameter, in order, added to the outputs
+    pub fn gradient(mut self, expression: Expression<I>) -> Self {
+        self.outputs.push(Output::Gradient (expression));
+        self
+    }
+
+    /// Returns the `Codegen` with the parameters of the generated function given explicitly
+    ///
+    /// (By default, the parameters are the free variables of the outputs ordered by name)
+    pub fn parameters(self, parameters: Vec<I>) -> Self {
+        Self { parameters: Some (parameters), ..self }
+    }
+
+    /// Returns the parameters of the generated function
+    fn resolved_parameters(&self) -> Vec<I> {
+        if let Some (parameters) = &self.parameters { return parameters.clone() }
+        let mut dag: Dag<I> = Dag::new();
+        for output in &self.outputs {
+            let (Output::Value (expression) | Output::Gradient (expression)) = output;
+            dag.insert(expression);
+        }
+        let mut parameters: Vec<I> = (0..dag.len())
+            .filter_map(|identifier| match dag.node(identifier) {
+                Node::Variable (name) => Some (name.clone()),
+                _ => None,
+            })
+            .collect();
+        parameters.sort_by_key(|parameter| parameter.to_string());
+        parameters
+    }
+
+    /// Returns the expressions of the values returned by the generated function
+    fn expressions(&self, parameters: &[I]) -> Vec<Expression<I>> {
+        let mut expressions: Vec<Expression<I>> = Vec::new();
+        for output in &self.outputs {
+            match output {
+                Output::Value (expression) => expressions.push(expression.clone()),
+                Output::Gradient (expression) => {
+                    expressions.push(expression.clone());
+                    expressions.extend(parameters.iter()
+                        .map(|parameter| expression.differentiate(parameter).reduce()));
+                }
+            }
+        }
+        expressions
+    }
+
+    /// Writes the generated function
+    fn write<W: Write>(&self, w: &mut W) -> FormatResult {
+        let parameters: Vec<I> = self.resolved_parameters();
+        let mut dag: Dag<I> = Dag::new();
+        let roots: Vec<usize> = self.expressions(&parameters).iter()
+            .map(|expression| dag.insert(expression))
+            .collect();
+        let (bindings, results) = Bindings::eliminate(&dag, &roots, &self.repeated(&dag));
+        let names: Vec<String> = parameters.iter().map(ToString::to_string).collect();
+        let count: usize = results.len();
+        let results: Vec<String> = results.iter()
+            .map(|result| self.render(result).0)
+            .collect();
+        match self.language {
+            Language::Rust => {
+                let parameters: Vec<String> = names.iter()
+                    .map(|name| format!("{}: f64", name))
+                    .collect();
+                let output: String = if count == 1 { "f64".to_string() } else {
+                    format!("[f64; {}]", count)
+                };
+                writeln!(w, "pub fn {}({}) -> {} {{", self.name, parameters.join(", "), output)?;
+                for (index, binding) in bindings.iter().enumerate() {
+                    writeln!(w, "    let t{}: f64 = {};", index, self.render(binding).0)?;
+                }
+                if count == 1 { writeln!(w, "    {}", results[0])? } else {
+                    writeln!(w, "    [{}]", results.join(", "))?
+                }
+                w.write_str("}\n")
+            }
+            Language::C => {
+                let mut parameters: Vec<String> = names.iter()
+                    .map(|name| format!("double {}", name))
+                    .collect();
+                w.write_str("#include <math.h>\n\n")?;
+                if count == 1 {
+                    writeln!(w, "double {}({}) {{", self.name, parameters.join(", "))?;
+                } else {
+                    // several values are written to an array supplied by the caller
+                    parameters.push("double *out".to_string());
+                    writeln!(w, "void {}({}) {{", self.name, parameters.join(", "))?;
+                }
+                for (index, binding) in bindings.iter().enumerate() {
+                    writeln!(w, "    const double t{} = {};", index, self.render(binding).0)?;
+                }
+                if count == 1 { writeln!(w, "    return {};", results[0])? } else {
+                    for (index, result) in results.iter().enumerate() {
+                        writeln!(w, "    out[{}] = {};", index, result)?;
+                    }
+                }
+                w.write_str("}\n")
+            }
+            Language::Python => {
+                w.write_str("import numpy as np\n\n")?;
+                writeln!(w, "def {}({}):", self.name, names.join(", "))?;
+                for (index, binding) in bindings.iter().enumerate() {
+                    writeln!(w, "    t{} = {}", index, self.render(binding).0)?;
+                }
+                writeln!(w, "    return {}", results.join(", "))
+            }
+            Language::Glsl => {
+                let parameters: Vec<String> = names.iter()
+                    .map(|name| format!("float {}", name))
+                    .collect();
+                let output: String = match count {
+                    1 => "float".to_string(),
+                    2..=4 => format!("vec{}", count),
+                    _ => format!("float[{}]", count),
+                };
+                writeln!(w, "{} {}({}) {{", output, self.name, parameters.join(", "))?;
+                for (index, binding) in bindings.iter().enumerate() {
+                    writeln!(w, "    float t{} = {};", index, self.render(binding).0)?;
+                }
+                if count == 1 { writeln!(w, "    return {};", results[0])? } else {
+                    writeln!(w, "    return {}({});", output, results.join(", "))?
+                }
+                w.write_str("}\n")
+            }
+        }
+    }
+
+    /// Returns the nodes of a `Dag` that are rendered more than once by the nodes using them,
+    /// which are the bases of powers expanded into products and the operands of signs written
+    /// as conditional expressions
+    fn repeated(&self, dag: &Dag<I>) -> Vec<usize> {
+        (0..dag.len())
+            .filter_map(|identifier| match (self.language, dag.node(identifier)) {
+                (Language::Glsl, Node::Power (base, exponent)) => match dag.node(*exponent) {
+                    Node::Integer (integer) => integer.to_i32()
+                        .filter(|integer| (2..=EXPANDED).contains(&integer.saturating_abs()))
+                        .map(|_| *base),
+                    _ => None,
+                },
+                (Language::Rust | Language::C, Node::Sign (operand)) => Some (*operand),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Renders an `Expression` as source code, and returns it with its `Precedence`
+    fn render(&self, expression: &Expression<Symbol<I>>) -> (String, Precedence) {
+        use Expression::*;
+        if let Some (positive) = negation(expression) {
+            return (format!("-{}", self.operand(&positive, Precedence::Product)),
+                Precedence::Negation)
+        }
+        match expression {
+            Sum (terms) => match terms.split_first() {
+                Some ((first, [])) => self.render(first),
+                Some ((first, rest)) => {
+                    let mut source: String = self.operand(first, Precedence::Negation);
+                    for term in rest {
+                        match negation(term) {
+                            Some (positive) => {
+                                source += " - ";
+                                source += &self.operand(&positive, Precedence::Product);
+                            }
+                            None => {
+                                source += " + ";
+                                source += &self.operand(term, Precedence::Negation);
+                            }
+                        }
+                    }
+                    (source, Precedence::Sum)
+                }
+                None => (self.literal(&BigInt::ZERO), Precedence::Atom),
+            }
+            Product (factors) => match factors.as_slice() {
+                [] => (self.literal(&BigInt::from(1)), Precedence::Atom),
+                [factor] => self.render(factor),
+                factors => {
+                    let factors: Vec<String> = factors.iter()
+                        .map(|factor| self.operand(factor, Precedence::Product))
+                        .collect();
+                    (factors.join(" * "), Precedence::Product)
+                }
+            }
+            Quotient (operands) => (
+                format!(
+                    "{} / {}",
+                    self.operand(&operands.0, Precedence::Product),
+                    self.operand(&operands.1, Precedence::Power),
+                ),
+                Precedence::Product,
+            ),
+            Power (operands) => self.power(&operands.0, &operands.1),
+            Exponential (operand) => (self.call("exp", operand), Precedence::Atom),
+            Logarithm (operand) => (self.call("log", operand), Precedence::Atom),
+            Abs (operand) => (self.call("abs", operand), Precedence::Atom),
+            Sign (operand) => match self.language {
+                Language::Python | Language::Glsl => (self.call("sign", operand), Precedence::Atom),
+                // the sign of zero or of an undefined value is the value itself, and the operand
+                // is stored in a local variable, so that it is computed once
+                Language::Rust | Language::C => {
+                    let operand: String = self.operand(operand, Precedence::Sum);
+                    self.conditional(
+                        vec![
+                            (format!("{} > 0.0", operand), "1.0".to_string()),
+                            (format!("{} < 0.0", operand), "-1.0".to_string()),
+                        ],
+                        operand,
+                    )
+                }
+            }
+            Piecewise (branches) => {
+                let branches: Vec<(String, String)> = branches.iter()
+                    .map(|(condition, branch)| {
+                        let operator: &str = match condition.comparison() {
+                            Comparison::Positive => ">",
+                            Comparison::NonNegative => ">=",
+                            Comparison::NonZero => "!=",
+                        };
+                        let compared: String = self.operand(
+                            condition.expression(),
+                            Precedence::Sum,
+                        );
+                        (format!("{} {} 0.0", compared, operator), self.render(branch).0)
+                    })
+                    .collect();
+                let undefined: &str = match self.language {
+                    Language::Rust => "f64::NAN",
+                    Language::C => "NAN",
+                    Language::Python => "np.nan",
+                    Language::Glsl => "0.0 / 0.0",
+                };
+                self.conditional(branches, undefined.to_string())
+            }
+            Function (name, arguments) => {
+                let arguments: Vec<String> = arguments.iter()
+                    .map(|argument| self.render(argument).0)
+                    .collect();
+                (format!("{}({})", Self::symbol(name), arguments.join(", ")), Precedence::Atom)
+            }
+            Derivative (name, indices, arguments) => {
+                let partials: String = indices.iter()
+                    .map(|index| format!("_d{}", index + 1))
+                    .collect();
+                let arguments: Vec<String> = arguments.iter()
+                    .map(|argument| self.render(argument).0)
+                    .collect();
+                (
+                    format!("{}{}({})", Self::symbol(name), partials, arguments.join(", ")),
+                    Precedence::Atom,
+                )
+            }
+            Variable (symbol) => (Self::symbol(symbol), Precedence::Atom),
+            Integer (integer) => (self.literal(integer), Precedence::Atom),
+        }
+    }
+
+    /// Renders a power as source code, with a square root or an integer power where possible
+    ///
+    /// (Negative integer exponents are written as reciprocals, since NumPy does not raise
+    /// integers to them, and GLSL's `pow` is undefined for negative bases, so small integer
+    /// powers are expanded into products there)
+    fn power(
+        &self,
+        base: &Expression<Symbol<I>>,
+        exponent: &Expression<Symbol<I>>,
+    ) -> (String, Precedence) {
+        if root_index(exponent) == Some (BigInt::from(2)) {
+            return (self.call("sqrt", base), Precedence::Atom)
+        }
+        let integer: Option<i32> = match exponent {
+            Expression::Integer (integer) => integer.to_i32(),
+            _ => None,
+        };
+        let whole = |expression: &Expression<Symbol<I>>| self.render(expression).0;
+        match (self.language, integer) {
+            (_, Some (integer)) if integer >= 0 => self.integer_power(base, integer),
+            (_, Some (integer)) if integer != i32::MIN => {
+                let (source, precedence): (String, Precedence) =
+                    self.integer_power(base, -integer);
+                let divisor: String = if precedence < Precedence::Power {
+                    format!("({})", source)
+                } else { source };
+                (format!("1.0 / {}", divisor), Precedence::Product)
+            }
+            (Language::Rust, _) =>
+                (format!("f64::powf({}, {})", whole(base), whole(exponent)), Precedence::Atom),
+            (Language::Python, _) => (
+                format!(
+                    "{} ** {}",
+                    self.operand(base, Precedence::Atom),
+                    self.operand(exponent, Precedence::Atom),
+                ),
+                Precedence::Power,
+            ),
+            (Language::C | Language::Glsl, _) =>
+                (format!("pow({}, {})", whole(base), whole(exponent)), Precedence::Atom),
+        }
+    }
+
+    /// Renders a power with a non-negative integer exponent as source code
+    ///
+    /// (The bases of powers expanded into products are stored in local variables, so that
+    /// they are computed once)
+    fn integer_power(&self, base: &Expression<Symbol<I>>, integer: i32) -> (String, Precedence) {
+        match self.language {
+            Language::Rust =>
+                (format!("f64::powi({}, {})", self.render(base).0, integer), Precedence::Atom),
+            Language::Python => (
+                format!("{} ** {}", self.operand(base, Precedence::Atom), integer),
+                Precedence::Power,
+            ),
+            Language::Glsl if integer == 0 => (self.literal(&BigInt::from(1)), Precedence::Atom),
+            Language::Glsl if integer == 1 => self.render(base),
+            Language::Glsl if integer <= EXPANDED => (
+                vec![self.operand(base, Precedence::Product); integer as usize].join(" * "),
+                Precedence::Product,
+            ),
+            Language::C | Language::Glsl => (
+                format!("pow({}, {})", self.render(base).0, self.literal(&BigInt::from(integer))),
+                Precedence::Atom,
+            ),
+        }
+    }
+
+    /// Renders a conditional expression as source code, from the source code of the condition
+    /// and value of each branch and of the value where no condition holds
+    fn conditional(
+        &self,
+        branches: Vec<(String, String)>,
+        otherwise: String,
+    ) -> (String, Precedence) {
+        if branches.is_empty() { return (otherwise, Precedence::Conditional) }
+        match self.language {
+            Language::Rust => {
+                let branches: Vec<String> = branches.into_iter()
+                    .map(|(condition, value)| format!("if {} {{ {} }}", condition, value))
+                    .collect();
+                (
+                    format!("{} else {{ {} }}", branches.join(" else "), otherwise),
+                    Precedence::Conditional,
+                )
+            }
+            Language::C | Language::Glsl => {
+                let source: String = branches.into_iter()
+                    .map(|(condition, value)| format!("{} ? {} : ", condition, value))
+                    .collect();
+                (source + &otherwise, Precedence::Conditional)
+            }
+            // NumPy selects elementwise, so that arrays can be passed as arguments
+            Language::Python => {
+                let (conditions, values): (Vec<String>, Vec<String>) = branches.into_iter()
+                    .unzip();
+                (
+                    format!(
+                        "np.select([{}], [{}], {})",
+                        conditions.join(", "),
+                        values.join(", "),
+                        otherwise,
+                    ),
+                    Precedence::Atom,
+                )
+            }
+        }
+    }
+
+    /// Renders the call of a mathematical function on an operand as source code
+    fn call(&self, function: &str, operand: &Expression<Symbol<I>>) -> String {
+        let operand: String = self.render(operand).0;
+        match (self.language, function) {
+            (Language::Rust, "log") => format!("f64::ln({})", operand),
+            (Language::C, "abs") => format!("fabs({})", operand),
+            (Language::Rust, function) => format!("f64::{}({})", function, operand),
+            (Language::Python, function) => format!("np.{}({})", function, operand),
+            (Language::C | Language::Glsl, function) => format!("{}({})", function, operand),
+        }
+    }
+
+    /// Renders a `Symbol` as the name of a parameter, a local variable or a function
+    fn symbol(symbol: &Symbol<I>) -> String {
+        match symbol {
+            Symbol::Variable (name) => name.to_string(),
+            Symbol::Binding (index) => format!("t{}", index),
+        }
+    }
+
+    /// Renders an operand as source code, enclosed in parentheses if its `Precedence` is lower
+    /// than a minimum
+    fn operand(&self, expression: &Expression<Symbol<I>>, minimum: Precedence) -> String {
+        let (source, precedence): (String, Precedence) = self.render(expression);
+        if precedence < minimum { format!("({})", source) } else { source }
+    }
+
+    /// Renders an integer as a floating-point literal
+    fn literal(&self, integer: &BigInt) -> String { format!("{}.0", integer) }
+
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Display for Codegen<I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult { self.write(f) }
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Returns the source code of a function in a `Language` that computes the value of an
+    /// `Expression` from its free variables
+    pub fn codegen(&self, language: Language, name: &str) -> Codegen<I> {
+        Codegen::new(language, name).output(self.clone())
+    }
+
+}
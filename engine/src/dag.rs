@@ -0,0 +1,850 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    exact::integer_power,
+    Assumption,
+    Assumptions,
+    Bindings,
+    Comparison,
+    Condition,
+    EvaluationError,
+    Expression,
+    Numeric,
+};
+use num::{
+    bigint::BigInt,
+    integer::Integer,
+    rational::BigRational,
+    traits::{
+        Signed,
+        ToPrimitive,
+    },
+    One,
+    Zero,
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+};
+
+/// A node in a `Dag`, whose operands are identifiers of other nodes in the same `Dag`
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub enum Node<I: Clone + Eq + Hash + PartialEq = usize> {
+
+    /// Addition of terms
+    Sum (Vec<usize>),
+
+    /// Multiplication of terms
+    Product (Vec<usize>),
+
+    /// Division of a term by another
+    Quotient (usize, usize),
+
+    /// Exponentiation of a term to another as a power
+    Power (usize, usize),
+
+    /// Application of the exponential function to a term
+    Exponential (usize),
+
+    /// Application of the natural logarithm function to a term
+    Logarithm (usize),
+
+    /// Absolute value of a term
+    Abs (usize),
+
+    /// Sign of a term
+    Sign (usize),
+
+    /// The first of a list of branches whose term satisfies its `Comparison`, with the term
+    /// compared and the term of the branch
+    Piecewise (Vec<(Comparison, usize, usize)>),
+
+    /// Application of a named function to arguments
+    Function (I, Vec<usize>),
+
+    /// Application of a partial derivative of a named function to arguments, with the indices
+    /// of the arguments that it is differentiated with respect to in ascending order
+    Derivative (I, Vec<usize>, Vec<usize>),
+
+    /// A variable
+    Variable (I),
+
+    /// An integer
+    Integer (BigInt),
+
+}
+
+/// A hash-consed directed acyclic graph of expressions, in which identical subexpressions
+/// are stored only once and shared between all of the expressions that contain them
+///
+/// (Operands are always inserted before the nodes that use them, so node identifiers are
+/// in topological order)
+pub struct Dag<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The nodes in the `Dag`
+    nodes: Vec<Node<I>>,
+    /// The identifiers of the nodes in the `Dag`
+    identifiers: HashMap<Node<I>, usize>,
+    /// Memoized reductions of nodes
+    reductions: HashMap<usize, usize>,
+    /// Memoized derivatives of nodes with respect to variables
+    derivatives: HashMap<(usize, I), usize>,
+    /// Memoized properties of nodes under the `Assumptions`
+    properties: HashMap<(usize, Assumption), bool>,
+    /// Memoized dependencies of nodes on variables
+    dependencies: HashMap<(usize, I), bool>,
+    /// The `Assumptions` consulted by reductions
+    assumptions: Assumptions<I>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Dag<I> {
+
+    /// Creates a new `Dag`
+    pub fn new() -> Self { Self::with_assumptions(Assumptions::new()) }
+
+    /// Creates a new `Dag` whose reductions consult some `Assumptions`
+    pub fn with_assumptions(assumptions: Assumptions<I>) -> Self {
+        Self {
+            nodes: Vec::new(),
+            identifiers: HashMap::new(),
+            reductions: HashMap::new(),
+            derivatives: HashMap::new(),
+            properties: HashMap::new(),
+            dependencies: HashMap::new(),
+            assumptions,
+        }
+    }
+
+    /// Returns the number of distinct nodes in the `Dag`
+    pub fn len(&self) -> usize { self.nodes.len() }
+
+    /// Returns `true` if the `Dag` contains no nodes
+    pub fn is_empty(&self) -> bool { self.nodes.is_empty() }
+
+    /// Returns the `Node` with a given identifier
+    pub fn node(&self, identifier: usize) -> &Node<I> { &self.nodes[identifier] }
+
+    /// Adds a `Node` to the `Dag` if not already present, and returns its identifier
+    pub fn add(&mut self, node: Node<I>) -> usize {
+        if let Some (identifier) = self.identifiers.get(&node) { return *identifier }
+        let identifier: usize = self.nodes.len();
+        self.identifiers.insert(node.clone(), identifier);
+        self.nodes.push(node);
+        identifier
+    }
+
+    /// Adds an `Expression` to the `Dag`, and returns the identifier of its root node
+    pub fn insert(&mut self, expression: &Expression<I>) -> usize {
+        use Expression::*;
+        let node: Node<I> = match expression {
+            Sum (terms) => Node::Sum (terms.iter()
+                .map(|term| self.insert(term))
+                .collect()
+            ),
+            Product (factors) => Node::Product (factors.iter()
+                .map(|factor| self.insert(factor))
+                .collect()
+            ),
+            Quotient (operands) => Node::Quotient (
+                self.insert(&operands.0),
+                self.insert(&operands.1),
+            ),
+            Power (operands) => Node::Power (
+                self.insert(&operands.0),
+                self.insert(&operands.1),
+            ),
+            Exponential (operand) => Node::Exponential (self.insert(operand)),
+            Logarithm (operand) => Node::Logarithm (self.insert(operand)),
+            Abs (operand) => Node::Abs (self.insert(operand)),
+            Sign (operand) => Node::Sign (self.insert(operand)),
+            Piecewise (branches) => Node::Piecewise (branches.iter()
+                .map(|(condition, branch)| (
+                    condition.comparison(),
+                    self.insert(condition.expression()),
+                    self.insert(branch),
+                ))
+                .collect()
+            ),
+            Function (name, arguments) => Node::Function (name.clone(), arguments.iter()
+                .map(|argument| self.insert(argument))
+                .collect()
+            ),
+            Derivative (name, indices, arguments) => Node::Derivative (
+                name.clone(),
+                indices.clone(),
+                arguments.iter().map(|argument| self.insert(argument)).collect(),
+            ),
+            Variable (identifier) => Node::Variable (identifier.clone()),
+            Integer (integer) => Node::Integer (integer.clone()),
+        };
+        self.add(node)
+    }
+
+    /// Converts a node back into an `Expression` tree
+    ///
+    /// (Shared subexpressions are duplicated in the output, so the result may be much larger
+    /// than the `Dag` itself)
+    pub fn expression(&self, identifier: usize) -> Expression<I> {
+        use Expression::*;
+        match &self.nodes[identifier] {
+            Node::Sum (terms) => Sum (terms.iter()
+                .map(|term| self.expression(*term))
+                .collect()
+            ),
+            Node::Product (factors) => Product (factors.iter()
+                .map(|factor| self.expression(*factor))
+                .collect()
+            ),
+            Node::Quotient (dividend, divisor) => Quotient (Box::new((
+                self.expression(*dividend),
+                self.expression(*divisor),
+            ))),
+            Node::Power (base, exponent) => Power (Box::new((
+                self.expression(*base),
+                self.expression(*exponent),
+            ))),
+            Node::Exponential (operand) => Exponential (Box::new(self.expression(*operand))),
+            Node::Logarithm (operand) => Logarithm (Box::new(self.expression(*operand))),
+            Node::Abs (operand) => Abs (Box::new(self.expression(*operand))),
+            Node::Sign (operand) => Sign (Box::new(self.expression(*operand))),
+            Node::Piecewise (branches) => Piecewise (branches.iter()
+                .map(|(comparison, compared, branch)| (
+                    Condition::new(*comparison, self.expression(*compared)),
+                    self.expression(*branch),
+                ))
+                .collect()
+            ),
+            Node::Function (name, arguments) => Function (name.clone(), arguments.iter()
+                .map(|argument| self.expression(*argument))
+                .collect()
+            ),
+            Node::Derivative (name, indices, arguments) => Derivative (
+                name.clone(),
+                indices.clone(),
+                arguments.iter().map(|argument| self.expression(*argument)).collect(),
+            ),
+            Node::Variable (identifier) => Variable (identifier.clone()),
+            Node::Integer (integer) => Integer (integer.clone()),
+        }
+    }
+
+    /// Adds an integer to the `Dag`, and returns its identifier
+    fn integer(&mut self, integer: impl Into<BigInt>) -> usize {
+        self.add(Node::Integer (integer.into()))
+    }
+
+    /// Returns the integer value of a node, if it is an integer
+    fn integer_value(&self, identifier: usize) -> Option<&BigInt> {
+        match &self.nodes[identifier] {
+            Node::Integer (integer) => Some (integer),
+            _ => None,
+        }
+    }
+
+    /// Returns the rational value of a node, if it is an integer or a quotient of integers
+    fn rational_value(&self, identifier: usize) -> Option<BigRational> {
+        match &self.nodes[identifier] {
+            Node::Integer (integer) => Some (BigRational::from_integer(integer.clone())),
+            Node::Quotient (dividend, divisor) => match (
+                self.integer_value(*dividend),
+                self.integer_value(*divisor),
+            ) {
+                (Some (numerator), Some (denominator)) if !denominator.is_zero() =>
+                    Some (BigRational::new(numerator.clone(), denominator.clone())),
+                _ => None,
+            }
+            _ => None,
+        }
+    }
+
+    /// Adds a rational number to the `Dag` as an integer or a quotient of integers, and
+    /// returns its identifier
+    fn rational(&mut self, rational: BigRational) -> usize {
+        let (numerator, denominator): (BigInt, BigInt) = rational.into_raw();
+        if denominator.is_one() { return self.integer(numerator) }
+        let numerator: usize = self.integer(numerator);
+        let denominator: usize = self.integer(denominator);
+        self.add(Node::Quotient (numerator, denominator))
+    }
+
+    /// Returns the operands of a node
+    pub fn operands(&self, identifier: usize) -> Vec<usize> {
+        match &self.nodes[identifier] {
+            Node::Sum (operands) | Node::Product (operands) | Node::Function (_, operands)
+            | Node::Derivative (_, _, operands) => operands.clone(),
+            Node::Quotient (a, b) | Node::Power (a, b) => vec![*a, *b],
+            Node::Exponential (operand) | Node::Logarithm (operand) | Node::Abs (operand)
+            | Node::Sign (operand) => vec![*operand],
+            Node::Piecewise (branches) => branches.iter()
+                .flat_map(|(_, compared, branch)| [*compared, *branch])
+                .collect(),
+            Node::Variable (_) | Node::Integer (_) => vec![],
+        }
+    }
+
+    /// Returns which of the nodes up to and including a root node are reachable from it
+    pub(crate) fn reachable(&self, root: usize) -> Vec<bool> {
+        let mut reachable: Vec<bool> = vec![false; root + 1];
+        reachable[root] = true;
+        for index in (0..=root).rev() {
+            if !reachable[index] { continue }
+            for operand in self.operands(index) { reachable[operand] = true }
+        }
+        reachable
+    }
+
+    /// Evaluates a node with a list of input values for a given variable, computing each
+    /// shared node only once
+    ///
+    /// (This method requires that no other unsubstituted variables remain below the node)
+    pub fn evaluate(
+        &self,
+        identifier: usize,
+        variable: &I,
+        values: &[f64],
+    ) -> Result<Vec<f64>, EvaluationError<I>> {
+        self.evaluate_with(identifier, variable, values)
+    }
+
+    /// Evaluates a node with a list of `Numeric` input values for a given variable, computing
+    /// each shared node only once
+    ///
+    /// (This method requires that no other unsubstituted variables remain below the node)
+    pub fn evaluate_with<T: Numeric>(
+        &self,
+        identifier: usize,
+        variable: &I,
+        values: &[T],
+    ) -> Result<Vec<T>, EvaluationError<I>> {
+        self.check_functions(identifier)?;
+        Bindings::from_dag(self, identifier).evaluate_with(variable, values)
+    }
+
+    /// Evaluates every node reachable from a root node at a single point, looking up the
+    /// values of variables with a function, and returns the values indexed by node
+    ///
+    /// (Nodes that are not reachable from the root are given the value of the sample)
+    pub(crate) fn evaluate_nodes<T: Numeric>(
+        &self,
+        root: usize,
+        sample: &T,
+        lookup: &dyn Fn(&I) -> Option<T>,
+    ) -> Result<Vec<T>, EvaluationError<I>> {
+        self.check_functions(root)?;
+        let reachable: Vec<bool> = self.reachable(root);
+        let mut values: Vec<T> = vec![sample.clone(); root + 1];
+        for index in (0..=root).filter(|index| reachable[*index]) {
+            let constant = |integer: i64| T::from_bigint(&BigInt::from(integer), sample);
+            values[index] = match &self.nodes[index] {
+                Node::Sum (terms) => terms.iter()
+                    .fold(constant(0), |sum, term| sum.add(&values[*term])),
+                Node::Product (factors) => factors.iter()
+                    .fold(constant(1), |product, factor| product.mul(&values[*factor])),
+                Node::Quotient (dividend, divisor) => values[*dividend].div(&values[*divisor]),
+                Node::Power (base, exponent) => values[*base].powf(&values[*exponent]),
+                Node::Exponential (operand) => values[*operand].exp(),
+                Node::Logarithm (operand) => values[*operand].ln(),
+                Node::Abs (operand) => values[*operand].abs(),
+                Node::Sign (operand) => values[*operand].signum(),
+                Node::Piecewise (branches) => {
+                    let selectable: Vec<(Comparison, T, T)> = branches.iter()
+                        .map(|(comparison, compared, branch)| (
+                            *comparison,
+                            values[*compared].clone(),
+                            values[*branch].clone(),
+                        ))
+                        .collect();
+                    T::select(&selectable, sample)
+                }
+                Node::Variable (name) => lookup(name)
+                    .ok_or_else(|| EvaluationError::UnboundVariable (name.clone()))?,
+                Node::Integer (integer) => T::from_bigint(integer, sample),
+                Node::Function (..) | Node::Derivative (..) =>
+                    unreachable!("functions are checked above"),
+            }
+        }
+        Ok (values)
+    }
+
+    /// Returns an `EvaluationError` if a function is applied below a node
+    fn check_functions(&self, root: usize) -> Result<(), EvaluationError<I>> {
+        for (index, _) in self.reachable(root).into_iter()
+            .enumerate()
+            .filter(|(_, reachable)| *reachable) {
+            if let Node::Function (name, _) | Node::Derivative (name, _, _) = &self.nodes[index] {
+                return Err (EvaluationError::UnexpandedFunction (name.clone()))
+            }
+        }
+        Ok (())
+    }
+
+    /// Reduces a node, or returns it unchanged if not reducible
+    ///
+    /// (Reductions are memoized, so each shared node is only reduced once, and rewrites that
+    /// are not valid for every value are only applied under the `Assumptions` of the `Dag`)
+    pub fn reduce(&mut self, identifier: usize) -> usize {
+        if let Some (reduced) = self.reductions.get(&identifier) { return *reduced }
+        let reduced: usize = match self.nodes[identifier].clone() {
+            Node::Sum (terms) => {
+                let mut integer_sum: BigInt = BigInt::ZERO;
+                let mut other_terms: Vec<usize> = Vec::new();
+                for term in terms {
+                    let term: usize = self.reduce(term);
+                    match self.nodes[term].clone() {
+                        Node::Sum (terms) => for term in terms {
+                            match self.integer_value(term) {
+                                Some (integer) => integer_sum += integer,
+                                None => other_terms.push(term),
+                            }
+                        },
+                        Node::Integer (integer) => integer_sum += integer,
+                        _ => other_terms.push(term),
+                    }
+                }
+                if !integer_sum.is_zero() { other_terms.push(self.integer(integer_sum)) }
+                match other_terms.len() {
+                    0 => self.integer(0),
+                    1 => other_terms[0],
+                    _ => self.add(Node::Sum (other_terms)),
+                }
+            }
+            Node::Product (factors) => {
+                let mut integer_product: BigInt = BigInt::one();
+                let mut other_factors: Vec<usize> = Vec::new();
+                for factor in factors {
+                    let factor: usize = self.reduce(factor);
+                    match self.nodes[factor].clone() {
+                        Node::Product (factors) => for factor in factors {
+                            match self.integer_value(factor) {
+                                Some (integer) => integer_product *= integer,
+                                None => other_factors.push(factor),
+                            }
+                        },
+                        Node::Integer (integer) => integer_product *= integer,
+                        _ => other_factors.push(factor),
+                    }
+                }
+                if integer_product.is_zero() {
+                    self.integer(0)
+                } else {
+                    if !integer_product.is_one() || other_factors.is_empty() {
+                        other_factors.push(self.integer(integer_product))
+                    }
+                    match other_factors.len() {
+                        1 => other_factors[0],
+                        _ => self.add(Node::Product (other_factors)),
+                    }
+                }
+            }
+            Node::Quotient (dividend, divisor) => {
+                let dividend: usize = self.reduce(dividend);
+                let divisor: usize = self.reduce(divisor);
+                let nonzero: bool = self.is_nonzero(divisor);
+                match (self.integer_value(dividend), self.integer_value(divisor)) {
+                    // x / x = 1 for nonzero x
+                    _ if dividend == divisor && nonzero => self.integer(1),
+                    // reduce fractions (leaving division by zero undefined)
+                    (Some (numerator), Some (denominator)) if !denominator.is_zero() => {
+                        let gcd: BigInt = numerator.gcd(denominator);
+                        let mut numerator: BigInt = numerator / &gcd;
+                        let mut denominator: BigInt = denominator / &gcd;
+                        if denominator < BigInt::ZERO {
+                            numerator = -numerator;
+                            denominator = -denominator;
+                        }
+                        if denominator.is_one() { self.integer(numerator) } else {
+                            let numerator: usize = self.integer(numerator);
+                            let denominator: usize = self.integer(denominator);
+                            self.add(Node::Quotient (numerator, denominator))
+                        }
+                    }
+                    // 0 / x = 0 for nonzero x
+                    (Some (numerator), _) if numerator.is_zero() && nonzero => self.integer(0),
+                    (_, Some (denominator)) if denominator.is_one() => dividend,
+                    _ => self.add(Node::Quotient (dividend, divisor)),
+                }
+            }
+            Node::Power (base, exponent) => {
+                let base: usize = self.reduce(base);
+                let exponent: usize = self.reduce(exponent);
+                let nonzero: bool = self.is_nonzero(base);
+                match (self.integer_value(base), self.integer_value(exponent)) {
+                    // powers too large to compute are left unevaluated
+                    (Some (base), Some (exponent)) if let Some (power) = integer_power(
+                        base,
+                        exponent,
+                    ) => self.integer(power),
+                    // x ^ 0 = 1 for nonzero x
+                    (_, Some (exponent)) if exponent.is_zero() && nonzero => self.integer(1),
+                    (_, Some (exponent)) if exponent.is_one() => base,
+                    _ => match self.nodes[base].clone() {
+                        // (x ^ a) ^ b = x ^ (a * b) for positive x, or nonzero x and integers
+                        // a and b
+                        Node::Power (inner_base, inner_exponent) if (
+                            self.is_positive(inner_base)
+                                && self.is_real(inner_exponent)
+                                && self.is_real(exponent)
+                        ) || (
+                            self.is_nonzero(inner_base)
+                                && self.integer_value(inner_exponent).is_some()
+                                && self.integer_value(exponent).is_some()
+                        ) => {
+                            let product: usize = match (
+                                self.rational_value(inner_exponent),
+                                self.rational_value(exponent),
+                            ) {
+                                (Some (a), Some (b)) => self.rational(a * b),
+                                _ => self.add(Node::Product (vec![inner_exponent, exponent])),
+                            };
+                            let power: usize = self.add(Node::Power (inner_base, product));
+                            self.reduce(power)
+                        }
+                        _ => self.add(Node::Power (base, exponent)),
+                    }
+                }
+            }
+            Node::Exponential (operand) => {
+                let operand: usize = self.reduce(operand);
+                match self.nodes[operand].clone() {
+                    // e ^ ln(x) = x for positive x
+                    Node::Logarithm (inner) if self.is_positive(inner) => inner,
+                    _ => self.add(Node::Exponential (operand)),
+                }
+            }
+            Node::Logarithm (operand) => {
+                let operand: usize = self.reduce(operand);
+                match self.nodes[operand].clone() {
+                    // ln(e ^ x) = x for real x
+                    Node::Exponential (inner) if self.is_real(inner) => inner,
+                    // ln(x ^ a) = a * ln(x) for positive x and real a
+                    Node::Power (base, exponent)
+                        if self.is_positive(base) && self.is_real(exponent) => {
+                        let logarithm: usize = self.add(Node::Logarithm (base));
+                        let product: usize = self.add(Node::Product (vec![exponent, logarithm]));
+                        self.reduce(product)
+                    }
+                    _ => self.add(Node::Logarithm (operand)),
+                }
+            }
+            Node::Function (name, arguments) => {
+                let arguments: Vec<usize> = arguments.into_iter()
+                    .map(|argument| self.reduce(argument))
+                    .collect();
+                self.add(Node::Function (name, arguments))
+            }
+            Node::Derivative (name, indices, arguments) => {
+                let arguments: Vec<usize> = arguments.into_iter()
+                    .map(|argument| self.reduce(argument))
+                    .collect();
+                self.add(Node::Derivative (name, indices, arguments))
+            }
+            Node::Abs (operand) => {
+                let operand: usize = self.reduce(operand);
+                match self.nodes[operand].clone() {
+                    Node::Integer (integer) => self.add(Node::Integer (integer.abs())),
+                    // |x| = x for positive x
+                    _ if self.is_positive(operand) => operand,
+                    // ||x|| = |x|
+                    Node::Abs (_) => operand,
+                    _ => self.add(Node::Abs (operand)),
+                }
+            }
+            Node::Sign (operand) => {
+                let operand: usize = self.reduce(operand);
+                match self.nodes[operand].clone() {
+                    Node::Integer (integer) => self.add(Node::Integer (integer.signum())),
+                    // sign(x) = 1 for positive x
+                    _ if self.is_positive(operand) => self.integer(1),
+                    _ => self.add(Node::Sign (operand)),
+                }
+            }
+            Node::Piecewise (branches) => {
+                let mut reduced: Vec<(Comparison, usize, usize)> = Vec::new();
+                let mut selected: Option<usize> = None;
+                for (comparison, compared, branch) in branches {
+                    let compared: usize = self.reduce(compared);
+                    let branch: usize = self.reduce(branch);
+                    // branches with integer conditions are always or never selected
+                    match self.integer_value(compared).map(|integer| integer.to_f64()) {
+                        Some (value) if comparison.holds(value.unwrap_or(f64::NAN)) => {
+                            if reduced.is_empty() { selected = Some (branch) } else {
+                                reduced.push((comparison, compared, branch));
+                            }
+                            break
+                        }
+                        Some (_) => {}
+                        None => reduced.push((comparison, compared, branch)),
+                    }
+                }
+                match selected {
+                    Some (branch) => branch,
+                    None => self.add(Node::Piecewise (reduced)),
+                }
+            }
+            Node::Variable (_) | Node::Integer (_) => identifier,
+        };
+        self.reductions.insert(identifier, reduced);
+        self.reductions.insert(reduced, reduced);
+        reduced
+    }
+
+    /// Returns `true` if a node is known to be greater than zero under the `Assumptions`
+    fn is_positive(&mut self, identifier: usize) -> bool {
+        self.holds(identifier, Assumption::Positive)
+    }
+
+    /// Returns `true` if a node is known not to be zero under the `Assumptions`
+    fn is_nonzero(&mut self, identifier: usize) -> bool {
+        self.holds(identifier, Assumption::NonZero)
+    }
+
+    /// Returns `true` if a node is known to be a real number under the `Assumptions`
+    fn is_real(&mut self, identifier: usize) -> bool {
+        self.holds(identifier, Assumption::Real)
+    }
+
+    /// Returns `true` if a property is known to hold for the value of a node under the
+    /// `Assumptions`, by the same rules as for `Expression`s
+    ///
+    /// (Properties are memoized, so each shared node is only examined once for each)
+    fn holds(&mut self, identifier: usize, property: Assumption) -> bool {
+        use Assumption::*;
+        if let Some (holds) = self.properties.get(&(identifier, property)) { return *holds }
+        let holds: bool = match (self.nodes[identifier].clone(), property) {
+            (Node::Variable (name), property) => self.assumptions.holds(&name, property),
+            (Node::Integer (integer), Positive) => integer.is_positive(),
+            (Node::Integer (integer), NonZero) => !integer.is_zero(),
+            (Node::Integer (_), Real | Integer) => true,
+            // nonzero values include positive ones
+            (_, NonZero) if self.is_positive(identifier) => true,
+            (Node::Sum (operands) | Node::Product (operands), Positive) => !operands.is_empty()
+                && operands.into_iter().all(|operand| self.holds(operand, Positive)),
+            (Node::Sum (_), NonZero) => false,
+            (Node::Sum (operands) | Node::Product (operands), property) => operands.into_iter()
+                .all(|operand| self.holds(operand, property)),
+            (Node::Quotient (dividend, divisor), Positive) =>
+                self.is_positive(dividend) && self.is_positive(divisor),
+            // quotients and powers must also be defined
+            (Node::Quotient (dividend, divisor), NonZero) =>
+                self.is_nonzero(dividend) && self.is_nonzero(divisor),
+            (Node::Quotient (dividend, divisor), Real) =>
+                self.is_real(dividend) && self.is_real(divisor) && self.is_nonzero(divisor),
+            (Node::Power (base, exponent), NonZero) => self.is_nonzero(base)
+                && ((self.is_positive(base) && self.is_real(exponent))
+                    || self.holds(exponent, Integer)),
+            (Node::Power (base, exponent), Positive) =>
+                self.is_positive(base) && self.is_real(exponent),
+            (Node::Power (base, exponent), Real) => self.is_real(exponent)
+                && (self.is_positive(base)
+                    || (self.is_real(base) && self.holds(exponent, Integer))),
+            (Node::Power (base, exponent), Integer) => self.holds(base, Integer)
+                && self.integer_value(exponent).is_some_and(|exponent| !exponent.is_negative()),
+            (Node::Exponential (operand), Positive | NonZero | Real) => self.is_real(operand),
+            (Node::Logarithm (operand), Real) => self.is_positive(operand),
+            (Node::Abs (operand), Positive | NonZero) => self.is_nonzero(operand),
+            (Node::Abs (_), Real) => true,
+            (Node::Abs (operand), Integer) => self.holds(operand, Integer),
+            (Node::Sign (operand), Positive | NonZero) => self.holds(operand, property),
+            (Node::Sign (operand), Real | Integer) => self.is_real(operand),
+            (Node::Piecewise (branches), property) => (!branches.is_empty()
+                || matches!(property, Real | Integer))
+                && branches.into_iter().all(|(_, _, branch)| self.holds(branch, property)),
+            (Node::Quotient (..) | Node::Exponential (_) | Node::Logarithm (_), _)
+            | (Node::Function (..) | Node::Derivative (..), _) => false,
+        };
+        self.properties.insert((identifier, property), holds);
+        holds
+    }
+
+    /// Returns `true` if a node depends on a variable
+    ///
+    /// (Dependencies are memoized, so each shared node is only examined once for each variable)
+    fn contains(&mut self, identifier: usize, variable: &I) -> bool {
+        let key: (usize, I) = (identifier, variable.clone());
+        if let Some (contains) = self.dependencies.get(&key) { return *contains }
+        let contains: bool = match &self.nodes[identifier] {
+            Node::Variable (name) => name == variable,
+            _ => self.operands(identifier).into_iter()
+                .any(|operand| self.contains(operand, variable)),
+        };
+        self.dependencies.insert(key, contains);
+        contains
+    }
+
+    /// Differentiates a node with respect to a variable, and returns the identifier of the
+    /// derivative
+    ///
+    /// (Derivatives are memoized, and reuse the original nodes instead of copying them, so the
+    /// size of the `Dag` grows at most linearly with each differentiation)
+    pub fn differentiate(&mut self, identifier: usize, variable: &I) -> usize {
+        let key: (usize, I) = (identifier, variable.clone());
+        if let Some (derivative) = self.derivatives.get(&key) { return *derivative }
+        let derivative: usize = match self.nodes[identifier].clone() {
+            // identity rule
+            Node::Variable (name) if &name == variable => self.integer(1),
+            // variable rule
+            Node::Variable (_) => self.integer(0),
+            // constant rule, even where `ln` of a constant base is undefined
+            _ if !self.contains(identifier, variable) => self.integer(0),
+            // sum rule
+            Node::Sum (terms) => {
+                let terms: Vec<usize> = terms.into_iter()
+                    .map(|term| self.differentiate(term, variable))
+                    .collect();
+                self.add(Node::Sum (terms))
+            }
+            // product rule
+            Node::Product (factors) => {
+                let terms: Vec<usize> = (0..factors.len())
+                    .map(|factor_index| {
+                        let mut output: Vec<usize> = Vec::with_capacity(factors.len());
+                        output.push(self.differentiate(factors[factor_index], variable));
+                        for (index, factor) in factors.iter().enumerate() {
+                            if index != factor_index { output.push(*factor) }
+                        }
+                        self.add(Node::Product (output))
+                    })
+                    .collect();
+                self.add(Node::Sum (terms))
+            }
+            // quotient rule
+            Node::Quotient (dividend, divisor) => {
+                let dividend_derivative: usize = self.differentiate(dividend, variable);
+                let divisor_derivative: usize = self.differentiate(divisor, variable);
+                let negative_one: usize = self.integer(-1);
+                let left: usize = self.add(Node::Product (vec![dividend_derivative, divisor]));
+                let right: usize = self.add(Node::Product (vec![
+                    dividend, divisor_derivative, negative_one
+                ]));
+                let numerator: usize = self.add(Node::Sum (vec![left, right]));
+                let denominator: usize = self.add(Node::Product (vec![divisor, divisor]));
+                self.add(Node::Quotient (numerator, denominator))
+            }
+            // power rules
+            Node::Power (base, exponent) => match (
+                self.contains(base, variable),
+                self.integer_value(exponent).cloned(),
+            ) {
+                // constant base shortcut
+                (false, _) => {
+                    let logarithm: usize = self.add(Node::Logarithm (base));
+                    let exponent_derivative: usize = self.differentiate(exponent, variable);
+                    self.add(Node::Product (vec![identifier, logarithm, exponent_derivative]))
+                }
+                // known exponent shortcut
+                (true, Some (integer)) => if integer.is_zero() {
+                    self.integer(0)
+                } else if integer.is_one() {
+                    self.differentiate(base, variable)
+                } else {
+                    let decremented: usize = self.integer(&integer - 1);
+                    let power: usize = self.add(Node::Power (base, decremented));
+                    let multiplier: usize = self.integer(integer);
+                    let base_derivative: usize = self.differentiate(base, variable);
+                    self.add(Node::Product (vec![multiplier, power, base_derivative]))
+                }
+                // constant exponent shortcut
+                (true, None) if !self.contains(exponent, variable) => {
+                    let negative_one: usize = self.integer(-1);
+                    let decremented: usize = self.add(Node::Sum (vec![exponent, negative_one]));
+                    let power: usize = self.add(Node::Power (base, decremented));
+                    let base_derivative: usize = self.differentiate(base, variable);
+                    self.add(Node::Product (vec![exponent, power, base_derivative]))
+                }
+                // general power rule
+                (true, None) => {
+                    let exponent_derivative: usize = self.differentiate(exponent, variable);
+                    let base_derivative: usize = self.differentiate(base, variable);
+                    let logarithm: usize = self.add(Node::Logarithm (base));
+                    let left: usize = self.add(Node::Product (vec![
+                        exponent_derivative, logarithm
+                    ]));
+                    let ratio: usize = self.add(Node::Quotient (base_derivative, base));
+                    let right: usize = self.add(Node::Product (vec![exponent, ratio]));
+                    let sum: usize = self.add(Node::Sum (vec![left, right]));
+                    self.add(Node::Product (vec![identifier, sum]))
+                }
+            }
+            // exponential rule
+            Node::Exponential (operand) => {
+                let operand_derivative: usize = self.differentiate(operand, variable);
+                self.add(Node::Product (vec![identifier, operand_derivative]))
+            }
+            // logarithm rule
+            Node::Logarithm (operand) => {
+                let operand_derivative: usize = self.differentiate(operand, variable);
+                self.add(Node::Quotient (operand_derivative, operand))
+            }
+            // absolute value rule, undefined where the term is zero
+            Node::Abs (operand) => {
+                let derivative: usize = self.differentiate(operand, variable);
+                let negative_one: usize = self.integer(-1);
+                let negated: usize = self.add(Node::Product (vec![negative_one, operand]));
+                let negated_derivative: usize = self.add(Node::Product (vec![
+                    negative_one,
+                    derivative,
+                ]));
+                self.add(Node::Piecewise (vec![
+                    (Comparison::Positive, operand, derivative),
+                    (Comparison::Positive, negated, negated_derivative),
+                ]))
+            }
+            // sign rule, undefined where the term is zero
+            Node::Sign (operand) => {
+                let zero: usize = self.integer(0);
+                self.add(Node::Piecewise (vec![(Comparison::NonZero, operand, zero)]))
+            }
+            // piecewise rule, undefined on the boundaries of the condition of the selected
+            // branch and earlier ones
+            Node::Piecewise (branches) => {
+                let mut boundaries: Vec<usize> = Vec::new();
+                let mut derivatives: Vec<(Comparison, usize, usize)> = Vec::new();
+                for (comparison, compared, branch) in branches {
+                    boundaries.push(compared);
+                    let boundary: usize = match boundaries.as_slice() {
+                        [boundary] => *boundary,
+                        boundaries => self.add(Node::Product (boundaries.to_vec())),
+                    };
+                    let derivative: usize = self.differentiate(branch, variable);
+                    let guarded: usize = self.add(Node::Piecewise (vec![
+                        (Comparison::NonZero, boundary, derivative),
+                    ]));
+                    derivatives.push((comparison, compared, guarded));
+                }
+                self.add(Node::Piecewise (derivatives))
+            }
+            // chain rule, with symbolic partial derivatives
+            Node::Function (name, arguments) => self.chain(name, &[], &arguments, variable),
+            Node::Derivative (name, indices, arguments) =>
+                self.chain(name, &indices, &arguments, variable),
+            Node::Integer (_) => unreachable!("integers do not contain variables"),
+        };
+        self.derivatives.insert(key, derivative);
+        derivative
+    }
+
+    /// Applies the chain rule to the application of a function, or of a partial derivative of
+    /// it, and returns the identifier of the derivative
+    fn chain(&mut self, name: I, indices: &[usize], arguments: &[usize], variable: &I) -> usize {
+        let terms: Vec<usize> = arguments.iter()
+            .enumerate()
+            .map(|(index, argument)| {
+                let mut indices: Vec<usize> = indices.to_vec();
+                indices.push(index);
+                indices.sort_unstable();
+                let partial: usize = self.add(Node::Derivative (
+                    name.clone(),
+                    indices,
+                    arguments.to_vec(),
+                ));
+                let argument_derivative: usize = self.differentiate(*argument, variable);
+                self.add(Node::Product (vec![partial, argument_derivative]))
+            })
+            .collect();
+        self.add(Node::Sum (terms))
+    }
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Default for Dag<I> {
+    fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,393 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Comparison,
+    Expression,
+    Numeric,
+};
+use num::{
+    bigint::{
+        BigInt,
+        Sign,
+    },
+    integer::Integer,
+    rational::BigRational,
+    traits::{
+        Signed,
+        ToPrimitive,
+    },
+    One,
+    Zero,
+};
+use std::{
+    cmp::Ordering,
+    fmt::{
+        Display,
+        Formatter,
+        Result as FormatResult,
+    },
+    hash::Hash,
+};
+
+/// The number of extra digits used in intermediate calculations
+const GUARD_DIGITS: u32 = 10;
+
+/// The largest magnitude of the exponent of a `Decimal`, leaving room for adjustments to
+/// exponents that cannot overflow
+const MAXIMUM_EXPONENT: i64 = 1 << 60;
+
+/// An arbitrary-precision decimal floating point number, with a given number of significant
+/// digits
+#[derive(Clone, Debug)]
+pub struct Decimal {
+    /// The significant digits of the `Decimal`
+    mantissa: BigInt,
+    /// The power of ten the mantissa is multiplied by
+    exponent: i64,
+    /// The maximum number of significant digits in the mantissa
+    precision: u32,
+}
+
+/// Returns ten to the power of a number, or `None` if the power is too large to compute
+fn power_of_ten(exponent: u64) -> Option<BigInt> {
+    Some (BigInt::from(10).pow(u32::try_from(exponent).ok()?))
+}
+
+/// Returns the sum of two exponents, or `None` if it is too large in magnitude
+fn exponent_sum(a: i64, b: i64) -> Option<i64> {
+    a.checked_add(b).filter(|sum| sum.abs() <= MAXIMUM_EXPONENT)
+}
+
+/// Returns the number of decimal digits in an integer
+fn digit_count(integer: &BigInt) -> i64 {
+    if integer.is_zero() { 0 } else { integer.magnitude().to_str_radix(10).len() as i64 }
+}
+
+impl Decimal {
+
+    /// Creates a new `Decimal` from a mantissa and exponent, rounding it to a precision
+    fn new(mantissa: BigInt, exponent: i64, precision: u32) -> Self {
+        let excess: i64 = digit_count(&mantissa) - precision as i64;
+        if mantissa.is_zero() {
+            Self { mantissa, exponent: 0, precision }
+        } else if excess > 0 {
+            let divisor: BigInt = power_of_ten(excess as u64).unwrap();
+            let (mut quotient, remainder): (BigInt, BigInt) = mantissa.div_rem(&divisor);
+            // round half away from zero
+            if remainder.magnitude() * 2u32 >= *divisor.magnitude() {
+                quotient += mantissa.signum();
+            }
+            Self::new(quotient, exponent + excess, precision)
+        } else { Self { mantissa, exponent, precision } }
+    }
+
+    /// Creates a `Decimal` from an integer with a given number of significant digits
+    pub fn from_integer(integer: BigInt, precision: u32) -> Self {
+        Self::new(integer, 0, precision.max(1))
+    }
+
+    /// Creates a `Decimal` from a rational number with a given number of significant digits
+    pub fn from_rational(rational: &BigRational, precision: u32) -> Self {
+        let working: u32 = precision.max(1) + GUARD_DIGITS;
+        Self::from_integer(rational.numer().clone(), working)
+            .divide(&Self::from_integer(rational.denom().clone(), working))
+            .unwrap()
+            .with_precision(precision)
+    }
+
+    /// Returns the `Decimal` rounded to a number of significant digits
+    pub fn with_precision(&self, precision: u32) -> Self {
+        Self::new(self.mantissa.clone(), self.exponent, precision.max(1))
+    }
+
+    /// Returns the number of significant digits of the `Decimal`
+    pub fn precision(&self) -> u32 { self.precision }
+
+    /// Returns `true` if the `Decimal` is zero
+    pub fn is_zero(&self) -> bool { self.mantissa.is_zero() }
+
+    /// Returns `true` if the `Decimal` is less than zero
+    pub fn is_negative(&self) -> bool { self.mantissa.is_negative() }
+
+    /// Returns the integer value of the `Decimal`, if it is an integer
+    pub fn to_integer(&self) -> Option<BigInt> {
+        if self.exponent >= 0 {
+            Some (&self.mantissa * power_of_ten(self.exponent as u64)?)
+        } else {
+            let (quotient, remainder): (BigInt, BigInt) = self.mantissa
+                .div_rem(&power_of_ten(self.exponent.unsigned_abs())?);
+            if remainder.is_zero() { Some (quotient) } else { None }
+        }
+    }
+
+    /// Returns the nearest `f64` to the `Decimal`
+    pub fn to_f64(&self) -> f64 {
+        format!("{}e{}", self.mantissa, self.exponent).parse().unwrap_or(f64::NAN)
+    }
+
+    /// Returns the power of ten of the most significant digit of the `Decimal`
+    fn magnitude(&self) -> i64 { self.exponent + digit_count(&self.mantissa) - 1 }
+
+    /// Returns the `Decimal` with the opposite sign
+    pub fn negate(&self) -> Self {
+        Self { mantissa: -&self.mantissa, exponent: self.exponent, precision: self.precision }
+    }
+
+    /// Adds two `Decimal`s
+    pub fn add(&self, other: &Self) -> Self {
+        let precision: u32 = self.precision.min(other.precision);
+        if other.is_zero() { return self.with_precision(precision) }
+        if self.is_zero() { return other.with_precision(precision) }
+        // terms too small to affect the rounded sum are ignored
+        let limit: i64 = precision as i64 + 2;
+        if self.magnitude() - other.magnitude() > limit { return self.with_precision(precision) }
+        if other.magnitude() - self.magnitude() > limit { return other.with_precision(precision) }
+        let exponent: i64 = self.exponent.min(other.exponent);
+        let scale = |decimal: &Self| &decimal.mantissa
+            * power_of_ten((decimal.exponent - exponent) as u64).unwrap();
+        Self::new(scale(self) + scale(other), exponent, precision)
+    }
+
+    /// Subtracts a `Decimal` from another
+    pub fn subtract(&self, other: &Self) -> Self { self.add(&other.negate()) }
+
+    /// Multiplies two `Decimal`s, or returns `None` if the product is too large or small to
+    /// represent
+    pub fn multiply(&self, other: &Self) -> Option<Self> {
+        Some (Self::new(
+            &self.mantissa * &other.mantissa,
+            exponent_sum(self.exponent, other.exponent)?,
+            self.precision.min(other.precision),
+        ))
+    }
+
+    /// Divides a `Decimal` by another, or returns `None` if the divisor is zero or the quotient
+    /// is too large or small to represent
+    pub fn divide(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() { return None }
+        let precision: u32 = self.precision.min(other.precision);
+        let scale: i64 = (precision as i64 + digit_count(&other.mantissa)
+            - digit_count(&self.mantissa) + 2).max(0);
+        Some (Self::new(
+            &self.mantissa * power_of_ten(scale as u64).unwrap() / &other.mantissa,
+            exponent_sum(self.exponent - scale, -other.exponent)?,
+            precision,
+        ))
+    }
+
+    /// Raises a `Decimal` to an integer power, or returns `None` if zero is raised to a
+    /// negative power or the power is too large or small to represent
+    pub fn powi(&self, exponent: &BigInt) -> Option<Self> {
+        let one: Self = Self::from_integer(BigInt::one(), self.precision);
+        if exponent.is_negative() { return one.divide(&self.powi(&-exponent)?) }
+        let working: Self = self.with_precision(self.precision + GUARD_DIGITS);
+        let mut output: Self = one.with_precision(working.precision);
+        // exponents that overflow end the squaring early
+        for bit in (0..exponent.bits()).rev() {
+            output = output.multiply(&output)?;
+            if exponent.bit(bit) { output = output.multiply(&working)? }
+        }
+        Some (output.with_precision(self.precision))
+    }
+
+    /// Returns the real `n`th root of a `Decimal`, or `None` if it does not exist
+    pub fn root(&self, n: u32) -> Option<Self> {
+        if n == 0 || (self.is_negative() && n.is_multiple_of(2)) { return None }
+        if self.is_zero() { return Some (self.clone()) }
+        // scale the mantissa so the exponent is divisible by `n` and the root has enough digits
+        let digits: i64 = (self.precision + GUARD_DIGITS) as i64 * n as i64;
+        let mut scale: i64 = (digits - digit_count(&self.mantissa)).max(0);
+        scale += (self.exponent - scale).rem_euclid(n as i64);
+        let mantissa: BigInt = &self.mantissa * power_of_ten(scale as u64).unwrap();
+        Some (Self::new(
+            mantissa.nth_root(n),
+            (self.exponent - scale) / n as i64,
+            self.precision,
+        ))
+    }
+
+    /// Applies the exponential function to a `Decimal`, or returns `None` if the result is too
+    /// large to represent
+    pub fn exp(&self) -> Option<Self> {
+        if self.is_zero() { return Some (Self::from_integer(BigInt::one(), self.precision)) }
+        let magnitude: f64 = self.to_f64().abs();
+        if magnitude.is_nan() || magnitude >= 1e15 { return None }
+        // halve the argument `halvings` times so the series converges quickly
+        let halvings: u32 = (magnitude.log2().ceil().max(0.0) as u32) + 8;
+        let precision: u32 = self.precision + GUARD_DIGITS + halvings / 3;
+        let fives: BigInt = BigInt::from(5).pow(halvings);
+        let argument: Self = Self::new(
+            &self.mantissa * fives,
+            self.exponent - halvings as i64,
+            precision,
+        );
+        // Taylor series
+        let mut sum: Self = Self::from_integer(BigInt::one(), precision);
+        let mut term: Self = sum.clone();
+        for n in 1.. {
+            term = term.multiply(&argument)?
+                .divide(&Self::from_integer(BigInt::from(n), precision))?;
+            if term.is_zero() || term.magnitude() < sum.magnitude() - precision as i64 { break }
+            sum = sum.add(&term);
+        }
+        // undo the halvings by squaring
+        for _ in 0..halvings { sum = sum.multiply(&sum)? }
+        Some (sum.with_precision(self.precision))
+    }
+
+    /// Applies the natural logarithm function to a `Decimal`, or returns `None` if it is not
+    /// positive
+    pub fn ln(&self) -> Option<Self> {
+        if self.is_zero() || self.is_negative() { return None }
+        let precision: u32 = self.precision + GUARD_DIGITS;
+        let working: Self = self.with_precision(precision);
+        // estimate from the leading digits
+        let leading: Self = self.with_precision(17);
+        let estimate: f64 = (leading.mantissa.to_f64().unwrap()).ln()
+            + leading.exponent as f64 * std::f64::consts::LN_10;
+        let mut output: Self = Self::from_f64(estimate, precision);
+        // Halley's method, which triples the number of correct digits with each iteration
+        let two: Self = Self::from_integer(BigInt::from(2), precision);
+        for _ in 0..64 {
+            let exponential: Self = output.exp()?;
+            let step: Self = two.multiply(&working.subtract(&exponential))?
+                .divide(&working.add(&exponential))?;
+            output = output.add(&step);
+            if step.is_zero()
+                || step.magnitude() < output.magnitude() - precision as i64
+                || step.magnitude() < -(precision as i64) { break }
+        }
+        Some (output.with_precision(self.precision))
+    }
+
+    /// Creates a `Decimal` from a finite `f64`
+    fn from_f64(float: f64, precision: u32) -> Self {
+        let rational: BigRational = BigRational::from_float(float).unwrap_or_default();
+        Self::from_rational(&rational, precision)
+    }
+
+    /// Raises a `Decimal` to the power of another, or returns `None` if the result is not real
+    ///
+    /// (As for other numbers, negative bases only have powers with integer exponents, since
+    /// an exponent such as `1/3` is not exactly representable)
+    pub fn powf(&self, exponent: &Self) -> Option<Self> {
+        if let Some (integer) = exponent.to_integer() { return self.powi(&integer) }
+        match self.mantissa.sign() {
+            Sign::Minus => None,
+            Sign::NoSign => if exponent.is_negative() { None } else { Some (self.clone()) },
+            Sign::Plus => {
+                let precision: u32 = self.precision + GUARD_DIGITS;
+                let logarithm: Self = self.with_precision(precision).ln()?;
+                Some (exponent.with_precision(precision).multiply(&logarithm)?.exp()?
+                    .with_precision(self.precision))
+            }
+        }
+    }
+
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some (self.cmp(other)) }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.mantissa.sign().cmp(&other.mantissa.sign()) {
+            Ordering::Equal => if self.is_zero() { return Ordering::Equal },
+            ordering => return ordering,
+        }
+        // numbers of the same sign with different magnitudes are ordered by magnitude
+        let ordering: Ordering = self.magnitude().cmp(&other.magnitude());
+        if ordering != Ordering::Equal {
+            return if self.is_negative() { ordering.reverse() } else { ordering }
+        }
+        let exponent: i64 = self.exponent.min(other.exponent);
+        let scale = |decimal: &Self| &decimal.mantissa
+            * power_of_ten((decimal.exponent - exponent) as u64).unwrap();
+        scale(self).cmp(&scale(other))
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        if self.is_zero() { return f.write_str("0") }
+        if self.is_negative() { f.write_str("-")? }
+        let digits: String = self.mantissa.magnitude().to_str_radix(10);
+        let digits: &str = digits.trim_end_matches('0');
+        let magnitude: i64 = self.magnitude();
+        if magnitude.abs() >= self.precision as i64 {
+            // scientific notation
+            let (first, rest): (&str, &str) = digits.split_at(1);
+            if rest.is_empty() { write!(f, "{}e{}", first, magnitude) } else {
+                write!(f, "{}.{}e{}", first, rest, magnitude)
+            }
+        } else if magnitude < 0 {
+            write!(f, "0.{}{}", "0".repeat((-magnitude - 1) as usize), digits)
+        } else if digits.len() as i64 <= magnitude + 1 {
+            write!(f, "{}{}", digits, "0".repeat((magnitude + 1) as usize - digits.len()))
+        } else {
+            let (whole, fraction): (&str, &str) = digits.split_at(magnitude as usize + 1);
+            write!(f, "{}.{}", whole, fraction)
+        }
+    }
+}
+
+/// Undefined results are represented by `None`, which propagates through every operation
+impl Numeric for Option<Decimal> {
+    fn from_bigint(integer: &BigInt, sample: &Self) -> Self {
+        Some (Decimal::from_integer(integer.clone(), sample.as_ref()?.precision))
+    }
+    fn add(&self, other: &Self) -> Self { Some (self.as_ref()?.add(other.as_ref()?)) }
+    fn mul(&self, other: &Self) -> Self { self.as_ref()?.multiply(other.as_ref()?) }
+    fn div(&self, other: &Self) -> Self { self.as_ref()?.divide(other.as_ref()?) }
+    fn powf(&self, exponent: &Self) -> Self { self.as_ref()?.powf(exponent.as_ref()?) }
+    fn exp(&self) -> Self { self.as_ref()?.exp() }
+    fn ln(&self) -> Self { self.as_ref()?.ln() }
+    fn abs(&self) -> Self {
+        let decimal: &Decimal = self.as_ref()?;
+        Some (if decimal.is_negative() { decimal.negate() } else { decimal.clone() })
+    }
+    fn signum(&self) -> Self {
+        let decimal: &Decimal = self.as_ref()?;
+        let sign: i32 = if decimal.is_zero() { 0 } else if decimal.is_negative() { -1 } else { 1 };
+        Some (Decimal::from_integer(BigInt::from(sign), decimal.precision))
+    }
+    fn select(branches: &[(Comparison, Self, Self)], _: &Self) -> Self {
+        for (comparison, compared, value) in branches {
+            let compared: &Decimal = compared.as_ref()?;
+            let holds: bool = match comparison {
+                Comparison::Positive => !compared.is_zero() && !compared.is_negative(),
+                Comparison::NonNegative => !compared.is_negative(),
+                Comparison::NonZero => !compared.is_zero(),
+            };
+            if holds { return value.clone() }
+        }
+        None
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Evaluates an `Expression` at a rational value for a given variable, to a number of
+    /// significant digits
+    ///
+    /// (This method returns `None` if the `Expression` is undefined at the value, or if other
+    /// unsubstituted variables remain in the `Expression`)
+    pub fn evaluate_decimal(
+        &self,
+        variable: &I,
+        value: &BigRational,
+        precision: u32,
+    ) -> Option<Decimal> {
+        let value: Decimal = Decimal::from_rational(value, precision + GUARD_DIGITS);
+        let output: Option<Decimal> = self.evaluate_with(variable, &[Some (value)]).ok()?.remove(0);
+        Some (output?.with_precision(precision))
+    }
+
+}
@@ -0,0 +1,192 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Expression,
+    Folder,
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+};
+
+/// The parameters and body of a named function, such as `f(x) = x^2 + 1`
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Definition<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The parameters of the function, in order
+    parameters: Vec<I>,
+    /// The body of the function, in terms of its parameters
+    body: Expression<I>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Definition<I> {
+
+    /// Creates a new `Definition` from the parameters and body of a function
+    pub fn new(parameters: Vec<I>, body: Expression<I>) -> Self { Self { parameters, body } }
+
+    /// Returns the parameters of the function, in order
+    pub fn parameters(&self) -> &[I] { &self.parameters }
+
+    /// Returns the body of the function
+    pub fn body(&self) -> &Expression<I> { &self.body }
+
+    /// Returns an `Expression` with every parameter in the body replaced by an argument, or
+    /// `None` if the number of arguments does not match the number of parameters
+    pub fn apply(&self, arguments: &[Expression<I>]) -> Option<Expression<I>> {
+        if arguments.len() != self.parameters.len() { return None }
+        Some (self.substitute(self.body.clone(), arguments))
+    }
+
+    /// Returns the body differentiated with respect to the parameters at some indices, in
+    /// terms of the parameters
+    ///
+    /// (Panics if an index is not the index of a parameter)
+    pub fn partial(&self, indices: &[usize], definitions: &Definitions<I>) -> Expression<I> {
+        self.partial_expanding(indices, definitions, &[])
+    }
+
+    /// Returns the body differentiated with respect to the parameters at some indices, within
+    /// the `Definitions` of some functions that are being expanded
+    pub(crate) fn partial_expanding(
+        &self,
+        indices: &[usize],
+        definitions: &Definitions<I>,
+        expanding: &[I],
+    ) -> Expression<I> {
+        indices.iter().fold(self.body.clone(), |partial, index| {
+            partial.differentiate_expanding(&self.parameters[*index], definitions, expanding)
+        })
+    }
+
+    /// Returns an `Expression` with every parameter replaced by an argument
+    pub(crate) fn substitute(
+        &self,
+        expression: Expression<I>,
+        arguments: &[Expression<I>],
+    ) -> Expression<I> {
+        Substitution { parameters: &self.parameters, arguments }.fold(expression)
+    }
+
+}
+
+/// The `Definition`s of named functions, which applications of them are expanded with
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Definitions<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The `Definition` of each function
+    functions: HashMap<I, Definition<I>>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Definitions<I> {
+
+    /// Creates new `Definitions` with no functions defined
+    pub fn new() -> Self { Self { functions: HashMap::new() } }
+
+    /// Defines a function, replacing any earlier `Definition` of it
+    pub fn define(&mut self, name: I, definition: Definition<I>) {
+        self.functions.insert(name, definition);
+    }
+
+    /// Returns `Definitions` with a function also defined
+    pub fn with(mut self, name: I, definition: Definition<I>) -> Self {
+        self.define(name, definition);
+        self
+    }
+
+    /// Returns the `Definition` of a function, if defined
+    pub fn get(&self, name: &I) -> Option<&Definition<I>> { self.functions.get(name) }
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Default for Definitions<I> {
+    fn default() -> Self { Self::new() }
+}
+
+/// A `Folder` that replaces the parameters of a `Definition` with arguments
+struct Substitution<'a, I: Clone + Eq + Hash + PartialEq> {
+    /// The parameters to replace
+    parameters: &'a [I],
+    /// The argument for each parameter
+    arguments: &'a [Expression<I>],
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Folder<I> for Substitution<'_, I> {
+
+    fn fold_variable(&mut self, variable: I) -> Expression<I> {
+        match self.parameters.iter().position(|parameter| parameter == &variable) {
+            Some (index) => self.arguments[index].clone(),
+            None => Expression::Variable (variable),
+        }
+    }
+
+    fn fold_function(&mut self, name: I, arguments: Vec<Expression<I>>) -> Expression<I> {
+        Expression::Function (name, arguments)
+    }
+
+    fn fold_derivative(
+        &mut self,
+        name: I,
+        indices: Vec<usize>,
+        arguments: Vec<Expression<I>>,
+    ) -> Expression<I> {
+        Expression::Derivative (name, indices, arguments)
+    }
+
+}
+
+/// A `Folder` that expands applications of defined functions and their derivatives
+struct Expansion<'a, I: Clone + Eq + Hash + PartialEq> {
+    /// The `Definition`s to expand applications with
+    definitions: &'a Definitions<I>,
+    /// The functions whose expansions are being folded, which are not expanded again
+    expanding: Vec<I>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Folder<I> for Expansion<'_, I> {
+
+    fn fold_variable(&mut self, variable: I) -> Expression<I> { Expression::Variable (variable) }
+
+    fn fold_function(&mut self, name: I, arguments: Vec<Expression<I>>) -> Expression<I> {
+        self.fold_derivative(name, Vec::new(), arguments)
+    }
+
+    fn fold_derivative(
+        &mut self,
+        name: I,
+        indices: Vec<usize>,
+        arguments: Vec<Expression<I>>,
+    ) -> Expression<I> {
+        let expanded: Option<Expression<I>> = match self.definitions.get(&name) {
+            Some (definition) if !self.expanding.contains(&name)
+                && definition.parameters.len() == arguments.len()
+                && indices.iter().all(|index| *index < arguments.len()) => Some (
+                definition.substitute(definition.partial(&indices, self.definitions), &arguments)
+            ),
+            _ => None,
+        };
+        match expanded {
+            Some (expanded) => {
+                self.expanding.push(name);
+                let expanded: Expression<I> = self.fold(expanded);
+                self.expanding.pop();
+                expanded
+            }
+            None if indices.is_empty() => Expression::Function (name, arguments),
+            None => Expression::Derivative (name, indices, arguments),
+        }
+    }
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Expands every application of a defined function, or of a partial derivative of it,
+    /// inline with its `Definition`, including applications within the expansions
+    ///
+    /// (Applications of undefined functions, with the wrong number of arguments, or of a
+    /// function within its own expansion are kept symbolic)
+    pub fn expand(&self, definitions: &Definitions<I>) -> Self {
+        Expansion { definitions, expanding: Vec::new() }.fold(self.clone())
+    }
+
+}
@@ -0,0 +1,429 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Dag,
+    EvaluationError,
+    Expression,
+    Interval,
+};
+use num::{
+    bigint::BigInt,
+    traits::One,
+};
+use std::{
+    collections::HashSet,
+    fmt::{
+        Display,
+        Formatter,
+        Result as FormatResult,
+    },
+    hash::Hash,
+    mem::{
+        discriminant,
+        Discriminant,
+    },
+};
+
+/// A comparison of a value with zero
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Comparison {
+
+    /// The value must be greater than zero
+    Positive,
+
+    /// The value must not be less than zero
+    NonNegative,
+
+    /// The value must not be zero
+    NonZero,
+
+}
+
+impl Comparison {
+
+    /// Returns `true` if a value satisfies the `Comparison`
+    ///
+    /// (`NaN` never satisfies a `Comparison`)
+    pub fn holds(self, value: f64) -> bool {
+        match self {
+            Comparison::Positive => value > 0.0,
+            Comparison::NonNegative => value >= 0.0,
+            Comparison::NonZero => value != 0.0 && !value.is_nan(),
+        }
+    }
+
+    /// Returns `Some (true)` if every value in an `Interval` satisfies the `Comparison`,
+    /// `Some (false)` if none does, or `None` if this cannot be decided
+    pub fn decide(self, enclosure: Interval) -> Option<bool> {
+        // empty enclosures come from values that are undefined everywhere
+        if enclosure.is_empty() { return Some (false) }
+        match self {
+            Comparison::Positive if enclosure.minimum() > 0.0 => Some (true),
+            Comparison::Positive if enclosure.maximum() <= 0.0 => Some (false),
+            Comparison::NonNegative if enclosure.minimum() >= 0.0 => Some (true),
+            Comparison::NonNegative if enclosure.maximum() < 0.0 => Some (false),
+            Comparison::NonZero if !enclosure.contains(0.0) => Some (true),
+            Comparison::NonZero if enclosure == Interval::point(0.0) => Some (false),
+            _ => None,
+        }
+    }
+
+}
+
+/// A condition on an `Expression` that must hold for another `Expression` to be defined, or
+/// for a branch of a piecewise `Expression` to be selected
+#[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Condition<I: Clone + Eq + Hash + PartialEq = usize> {
+
+    /// The `Expression` must be greater than zero (such as the argument of a logarithm)
+    Positive (Expression<I>),
+
+    /// The `Expression` must not be less than zero (such as the base of a fractional power)
+    NonNegative (Expression<I>),
+
+    /// The `Expression` must not be zero (such as the denominator of a quotient)
+    NonZero (Expression<I>),
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Condition<I> {
+
+    /// Creates a new `Condition` that an `Expression` satisfies a `Comparison`
+    pub fn new(comparison: Comparison, expression: Expression<I>) -> Self {
+        match comparison {
+            Comparison::Positive => Condition::Positive (expression),
+            Comparison::NonNegative => Condition::NonNegative (expression),
+            Comparison::NonZero => Condition::NonZero (expression),
+        }
+    }
+
+    /// Returns the `Comparison` that the `Condition` requires of its `Expression`
+    pub fn comparison(&self) -> Comparison {
+        match self {
+            Condition::Positive (_) => Comparison::Positive,
+            Condition::NonNegative (_) => Comparison::NonNegative,
+            Condition::NonZero (_) => Comparison::NonZero,
+        }
+    }
+
+    /// Returns the `Expression` that the `Condition` constrains
+    pub fn expression(&self) -> &Expression<I> {
+        match self {
+            Condition::Positive (expression)
+            | Condition::NonNegative (expression)
+            | Condition::NonZero (expression) => expression,
+        }
+    }
+
+    /// Returns a mutable reference to the `Expression` that the `Condition` constrains
+    pub(crate) fn expression_mut(&mut self) -> &mut Expression<I> {
+        match self {
+            Condition::Positive (expression)
+            | Condition::NonNegative (expression)
+            | Condition::NonZero (expression) => expression,
+        }
+    }
+
+    /// Returns the `Condition` with its `Expression` replaced by the result of a function,
+    /// which may change the type of its identifiers
+    pub fn map<J: Clone + Eq + Hash + PartialEq>(
+        self,
+        function: impl FnOnce(Expression<I>) -> Expression<J>,
+    ) -> Condition<J> {
+        let comparison: Comparison = self.comparison();
+        let expression: Expression<I> = match self {
+            Condition::Positive (expression)
+            | Condition::NonNegative (expression)
+            | Condition::NonZero (expression) => expression,
+        };
+        Condition::new(comparison, function(expression))
+    }
+
+    /// Returns `true` if the `Condition` holds for a value of a variable
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`)
+    pub fn holds(&self, variable: &I, value: f64) -> Result<bool, EvaluationError<I>> {
+        let output: f64 = self.expression().evaluate(variable, &[value])?[0];
+        Ok (self.holds_for(output))
+    }
+
+    /// Returns `true` if the `Condition` holds for a value of its `Expression`
+    fn holds_for(&self, output: f64) -> bool { self.comparison().holds(output) }
+
+    /// Returns `Some (true)` if the `Condition` holds everywhere in an `Interval` of values of a
+    /// variable, `Some (false)` if it holds nowhere, or `None` if this cannot be decided
+    fn decide(&self, variable: &I, input: Interval) -> Result<Option<bool>, EvaluationError<I>> {
+        let enclosure: Interval = self.expression().evaluate_interval(variable, input)?;
+        Ok (self.comparison().decide(enclosure))
+    }
+
+}
+
+/// Conditions are displayed as LaTeX inequalities
+impl Display for Condition<String> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            Condition::Positive (expression) => write!(f, "{} > 0", expression),
+            Condition::NonNegative (expression) => write!(f, "{} \\geq 0", expression),
+            Condition::NonZero (expression) => write!(f, "{} \\neq 0", expression),
+        }
+    }
+}
+
+/// The set of values of a variable for which an `Expression` is defined, described by the
+/// `Condition`s that must all hold, and by disjunctions of `Condition`s of which at least one
+/// must hold
+#[derive(Clone)]
+pub struct Domain<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The variable of the `Domain`
+    variable: I,
+    /// The conditions that must all hold
+    conditions: Vec<Condition<I>>,
+    /// The disjunctions of conditions, of which at least one condition must hold
+    disjunctions: Vec<Vec<Condition<I>>>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Domain<I> {
+
+    /// Returns the `Condition`s of the `Domain`
+    pub fn conditions(&self) -> &[Condition<I>] { &self.conditions }
+
+    /// Returns the disjunctions of the `Domain`, such as the conditions of the branches of a
+    /// piecewise `Expression`, of which at least one `Condition` must hold
+    pub fn disjunctions(&self) -> &[Vec<Condition<I>>] { &self.disjunctions }
+
+    /// Returns `true` if the `Domain` contains every real number
+    pub fn is_unrestricted(&self) -> bool {
+        self.conditions.is_empty() && self.disjunctions.is_empty()
+    }
+
+    /// Returns `true` if the `Domain` contains a value
+    ///
+    /// (This method requires that the `Condition`s contain no other variables)
+    pub fn contains(&self, value: f64) -> Result<bool, EvaluationError<I>> {
+        Ok (self.violations(value)?.is_empty())
+    }
+
+    /// Returns the `Condition`s that do not hold for a value, explaining why it is outside of
+    /// the `Domain`
+    ///
+    /// (Every `Condition` of a disjunction that does not hold is included, and this method
+    /// requires that the `Condition`s contain no other variables)
+    pub fn violations(&self, value: f64) -> Result<Vec<&Condition<I>>, EvaluationError<I>> {
+        let mut violations: Vec<&Condition<I>> = Vec::new();
+        for condition in self.conditions.iter() {
+            if !condition.holds(&self.variable, value)? { violations.push(condition) }
+        }
+        for disjunction in self.disjunctions.iter() {
+            let mut held: bool = false;
+            for condition in disjunction {
+                held |= condition.holds(&self.variable, value)?;
+            }
+            if !held { violations.extend(disjunction.iter()) }
+        }
+        Ok (violations)
+    }
+
+    /// Returns disjoint `Interval`s, in increasing order, contained in the `Domain` between two
+    /// finite bounds
+    ///
+    /// (The `Interval`s are found by bisection with interval arithmetic, and so exclude regions
+    /// no wider than the tolerance around the boundaries of the `Domain`)
+    pub fn intervals(
+        &self,
+        minimum: f64,
+        maximum: f64,
+        tolerance: f64,
+    ) -> Result<Vec<Interval>, EvaluationError<I>> {
+        let mut intervals: Vec<Interval> = Vec::new();
+        self.bisect(Interval::new(minimum, maximum), tolerance, &mut intervals)?;
+        Ok (intervals)
+    }
+
+    /// Adds the `Interval`s contained in the `Domain` within an `Interval`, merging those that
+    /// touch the last one added
+    fn bisect(
+        &self,
+        input: Interval,
+        tolerance: f64,
+        intervals: &mut Vec<Interval>,
+    ) -> Result<(), EvaluationError<I>> {
+        if input.is_empty() { return Ok (()) }
+        let mut decided: bool = true;
+        for condition in self.conditions.iter() {
+            match condition.decide(&self.variable, input)? {
+                Some (true) => {}
+                Some (false) => return Ok (()),
+                None => decided = false,
+            }
+        }
+        for disjunction in self.disjunctions.iter() {
+            // a disjunction holds if any condition holds, and fails if every condition fails
+            let mut decisions: Vec<Option<bool>> = Vec::with_capacity(disjunction.len());
+            for condition in disjunction {
+                decisions.push(condition.decide(&self.variable, input)?);
+            }
+            if decisions.contains(&Some (true)) { continue }
+            if decisions.iter().all(|decision| *decision == Some (false)) { return Ok (()) }
+            decided = false;
+        }
+        if decided {
+            match intervals.last_mut() {
+                Some (last) if last.maximum() == input.minimum() => *last = last.hull(input),
+                _ => intervals.push(input),
+            }
+        } else if input.width() > tolerance {
+            let middle: f64 = (input.minimum() + input.maximum()) / 2.0;
+            self.bisect(Interval::new(input.minimum(), middle), tolerance, intervals)?;
+            self.bisect(Interval::new(middle, input.maximum()), tolerance, intervals)?;
+        }
+        Ok (())
+    }
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Returns the `Domain` of an `Expression` with respect to a variable, combining the
+    /// conditions required by every logarithm, quotient and power within it
+    ///
+    /// (Functions are assumed to be defined wherever their arguments are, so applications of
+    /// them should be expanded first, and a piecewise `Expression` requires that one of its
+    /// branches is selected, and that the selected branch is defined)
+    pub fn domain(&self, variable: &I) -> Domain<I> {
+        let mut dag: Dag<I> = Dag::new();
+        let mut seen: HashSet<(Discriminant<Condition<I>>, usize)> = HashSet::new();
+        let mut conditions: Vec<Condition<I>> = Vec::new();
+        let mut disjunctions: Vec<Vec<Condition<I>>> = Vec::new();
+        self.conditions(&mut dag, &mut seen, &mut conditions, &mut disjunctions);
+        Domain { variable: variable.clone(), conditions, disjunctions }
+    }
+
+    /// Adds the `Condition`s and disjunctions of them required for an `Expression` to be
+    /// defined, skipping `Condition`s already seen
+    fn conditions(
+        &self,
+        dag: &mut Dag<I>,
+        seen: &mut HashSet<(Discriminant<Condition<I>>, usize)>,
+        conditions: &mut Vec<Condition<I>>,
+        disjunctions: &mut Vec<Vec<Condition<I>>>,
+    ) {
+        use Expression::*;
+        let required: Option<Condition<I>> = match self {
+            Quotient (operands) => Some (Condition::NonZero (operands.1.clone())),
+            Power (operands) => match operands.1.constant() {
+                Some (exponent) if exponent.fract() == 0.0 && exponent >= 0.0 => None,
+                Some (exponent) if exponent.fract() == 0.0 =>
+                    Some (Condition::NonZero (operands.0.clone())),
+                Some (exponent) if exponent > 0.0 =>
+                    Some (Condition::NonNegative (operands.0.clone())),
+                _ => Some (Condition::Positive (operands.0.clone())),
+            }
+            Logarithm (operand) => Some (Condition::Positive ((**operand).clone())),
+            // a single branch must be selected, and is otherwise part of a disjunction
+            Piecewise (branches) if branches.len() == 1 => Some (branches[0].0.clone()),
+            _ => None,
+        };
+        if let Some (condition) = required { Self::require(condition, dag, seen, conditions) }
+        match self {
+            Sum (operands) | Product (operands) => for operand in operands {
+                operand.conditions(dag, seen, conditions, disjunctions)
+            },
+            Quotient (operands) | Power (operands) => {
+                operands.0.conditions(dag, seen, conditions, disjunctions);
+                operands.1.conditions(dag, seen, conditions, disjunctions);
+            }
+            Exponential (operand) | Logarithm (operand) | Abs (operand) | Sign (operand) => {
+                operand.conditions(dag, seen, conditions, disjunctions)
+            }
+            Piecewise (branches) => {
+                for (condition, _) in branches {
+                    condition.expression().conditions(dag, seen, conditions, disjunctions)
+                }
+                if branches.len() == 1 {
+                    branches[0].1.conditions(dag, seen, conditions, disjunctions)
+                } else {
+                    // the conditions of other branches only hold where those are selected
+                    for (index, (_, body)) in branches.iter().enumerate() {
+                        let mut required: Vec<Condition<I>> = Vec::new();
+                        let mut alternatives: Vec<Vec<Condition<I>>> = Vec::new();
+                        let mut unseen: HashSet<(Discriminant<Condition<I>>, usize)> =
+                            HashSet::new();
+                        body.conditions(dag, &mut unseen, &mut required, &mut alternatives);
+                        for condition in required {
+                            let guarded: Condition<I> = Self::guarded(branches, index, condition);
+                            Self::require(guarded, dag, seen, conditions)
+                        }
+                        for alternative in alternatives {
+                            let disjunction: Vec<Condition<I>> = alternative.into_iter()
+                                .map(|condition| Self::guarded(branches, index, condition))
+                                .collect();
+                            if !disjunctions.contains(&disjunction) {
+                                disjunctions.push(disjunction)
+                            }
+                        }
+                    }
+                }
+                // disjunctions with a condition on a constant that holds always hold
+                let disjunction: Vec<Condition<I>> = branches.iter()
+                    .map(|(condition, _)| condition.clone())
+                    .collect();
+                if disjunction.len() > 1 && !disjunction.iter().any(|condition| condition
+                    .expression()
+                    .constant()
+                    .is_some_and(|output| condition.holds_for(output))
+                ) && !disjunctions.contains(&disjunction) {
+                    disjunctions.push(disjunction)
+                }
+            }
+            Function (_, arguments) | Derivative (_, _, arguments) => for argument in arguments {
+                argument.conditions(dag, seen, conditions, disjunctions)
+            },
+            Variable (_) | Integer (_) => {}
+        }
+    }
+
+    /// Adds a `Condition`, unless it has already been seen or is on a constant and holds
+    fn require(
+        condition: Condition<I>,
+        dag: &mut Dag<I>,
+        seen: &mut HashSet<(Discriminant<Condition<I>>, usize)>,
+        conditions: &mut Vec<Condition<I>>,
+    ) {
+        if condition.expression()
+            .constant()
+            .is_some_and(|output| condition.holds_for(output)) { return }
+        let identifier: usize = dag.insert(condition.expression());
+        if seen.insert((discriminant(&condition), identifier)) { conditions.push(condition) }
+    }
+
+    /// Returns a `Condition` that holds wherever a branch of a piecewise `Expression` is not
+    /// selected, and wherever another `Condition` holds where it is
+    ///
+    /// (The guarded `Expression` is one wherever the branch is not selected, which satisfies
+    /// every `Comparison`)
+    fn guarded(
+        branches: &[(Condition<I>, Expression<I>)],
+        index: usize,
+        condition: Condition<I>,
+    ) -> Condition<I> {
+        let one: Expression<I> = Expression::Integer (BigInt::one());
+        condition.map(|expression| {
+            let mut guarded: Vec<(Condition<I>, Expression<I>)> = branches[..index].iter()
+                .map(|(condition, _)| (condition.clone(), one.clone()))
+                .collect();
+            guarded.push((branches[index].0.clone(), expression));
+            guarded.push((Condition::Positive (one.clone()), one));
+            Expression::Piecewise (guarded)
+        })
+    }
+
+    /// Returns the value of an `Expression` containing no variables, if it is finite
+    fn constant(&self) -> Option<f64> {
+        let value: f64 = self.evaluate_variables(&[0.0], &|_| None).ok()?[0];
+        if value.is_finite() { Some (value) } else { None }
+    }
+
+}
@@ -0,0 +1,191 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Comparison,
+    EvaluationError,
+    Expression,
+    Numeric,
+};
+use num::{
+    bigint::BigInt,
+    traits::ToPrimitive,
+};
+use std::{
+    f64::consts::E,
+    hash::Hash,
+    slice::from_ref,
+};
+
+/// A dual number, carrying a value along with its partial derivatives with respect to a number
+/// of variables, for forward-mode automatic differentiation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dual {
+    /// The value of the `Dual`
+    value: f64,
+    /// The partial derivatives of the value
+    derivatives: Vec<f64>,
+}
+
+/// Returns the derivative of a power with respect to its base, which is `e * b ^ (e - 1)`
+///
+/// (A zero exponent makes the power constant, so the derivative is zero even at a zero base,
+/// where `b ^ (e - 1)` is infinite, but not at an undefined base)
+pub(crate) fn power_derivative(base: f64, exponent: f64) -> f64 {
+    if exponent == 0.0 && !base.is_nan() { 0.0 } else { exponent * base.powf(exponent - 1.0) }
+}
+
+/// Multiplies a derivative by a factor, where a zero derivative stays zero
+fn scale(derivative: f64, factor: f64) -> f64 {
+    if derivative == 0.0 { 0.0 } else { derivative * factor }
+}
+
+impl Dual {
+
+    /// Creates a `Dual` for a constant, with a number of zero partial derivatives
+    pub fn constant(value: f64, variables: usize) -> Self {
+        Self { value, derivatives: vec![0.0; variables] }
+    }
+
+    /// Creates a `Dual` for the variable with a given index out of a number of variables
+    pub fn variable(value: f64, index: usize, variables: usize) -> Self {
+        let mut derivatives: Vec<f64> = vec![0.0; variables];
+        derivatives[index] = 1.0;
+        Self { value, derivatives }
+    }
+
+    /// Returns the value of the `Dual`
+    pub fn value(&self) -> f64 { self.value }
+
+    /// Returns the partial derivatives of the `Dual`
+    pub fn derivatives(&self) -> &[f64] { &self.derivatives }
+
+    /// Creates a `Dual` from a value and the chain rule applied to another `Dual`
+    ///
+    /// (Zero derivatives stay zero even if the factor is undefined, so that terms that do not
+    /// depend on a variable are constant, like in symbolic differentiation)
+    fn chain(&self, value: f64, derivative: f64) -> Self {
+        Self {
+            value,
+            derivatives: self.derivatives.iter().map(|d| scale(*d, derivative)).collect(),
+        }
+    }
+
+    /// Creates a `Dual` from a value and a linear combination of the derivatives of two `Dual`s
+    ///
+    /// (Zero derivatives contribute nothing, as in `Dual::chain`)
+    fn combine(&self, other: &Self, value: f64, a: f64, b: f64) -> Self {
+        Self {
+            value,
+            derivatives: self.derivatives.iter()
+                .zip(other.derivatives.iter())
+                .map(|(x, y)| scale(*x, a) + scale(*y, b))
+                .collect(),
+        }
+    }
+
+}
+
+impl Numeric for Dual {
+    fn from_bigint(integer: &BigInt, sample: &Self) -> Self {
+        Self::constant(integer.to_f64().unwrap_or(f64::NAN), sample.derivatives.len())
+    }
+    fn add(&self, other: &Self) -> Self {
+        self.combine(other, self.value + other.value, 1.0, 1.0)
+    }
+    fn mul(&self, other: &Self) -> Self {
+        self.combine(other, self.value * other.value, other.value, self.value)
+    }
+    fn div(&self, other: &Self) -> Self {
+        let value: f64 = self.value / other.value;
+        self.combine(other, value, 1.0 / other.value, -value / other.value)
+    }
+    fn powf(&self, exponent: &Self) -> Self {
+        let value: f64 = Numeric::powf(&self.value, &exponent.value);
+        // constant exponents are differentiated without the logarithm of the base, which may
+        // be undefined
+        if exponent.derivatives.iter().all(|derivative| *derivative == 0.0) {
+            self.chain(value, power_derivative(self.value, exponent.value))
+        } else {
+            self.combine(
+                exponent,
+                value,
+                power_derivative(self.value, exponent.value),
+                value * self.value.ln(),
+            )
+        }
+    }
+    fn exp(&self) -> Self {
+        let value: f64 = E.powf(self.value);
+        self.chain(value, value)
+    }
+    fn ln(&self) -> Self { self.chain(self.value.ln(), 1.0 / self.value) }
+    // derivatives are undefined at kinks
+    fn abs(&self) -> Self {
+        let derivative: f64 = if self.value == 0.0 { f64::NAN } else { self.value.signum() };
+        self.chain(self.value.abs(), derivative)
+    }
+    fn signum(&self) -> Self {
+        // the sign is constant away from zero, even where the operand is too steep to differentiate
+        if self.value == 0.0 { self.chain(0.0, f64::NAN) } else {
+            Self::constant(self.value.signum(), self.derivatives.len())
+        }
+    }
+    fn select(branches: &[(Comparison, Self, Self)], sample: &Self) -> Self {
+        let mut kink: bool = false;
+        for (comparison, compared, value) in branches {
+            if compared.value.is_nan() { break }
+            kink |= compared.value == 0.0;
+            if comparison.holds(compared.value) {
+                return if kink { value.chain(value.value, f64::NAN) } else { value.clone() }
+            }
+        }
+        sample.chain(f64::NAN, f64::NAN)
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Evaluates the derivative of an `Expression` with respect to a variable at a list of
+    /// input values, using forward-mode automatic differentiation
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`)
+    pub fn evaluate_derivative(
+        &self,
+        variable: &I,
+        values: &[f64],
+    ) -> Result<Vec<f64>, EvaluationError<I>> {
+        let duals: Vec<Dual> = values.iter()
+            .map(|value| Dual::variable(*value, 0, 1))
+            .collect();
+        Ok (self.evaluate_with(variable, &duals)?.into_iter()
+            .map(|dual| dual.derivatives[0])
+            .collect())
+    }
+
+    /// Evaluates an `Expression` and its gradient with respect to a list of variables at a
+    /// point, using forward-mode automatic differentiation
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`)
+    pub fn gradient_forward(
+        &self,
+        variables: &[I],
+        point: &[f64],
+    ) -> Result<Dual, EvaluationError<I>> {
+        if point.len() != variables.len() { return Err (EvaluationError::MismatchedPoint {
+            variables: variables.len(),
+            values: point.len(),
+        })}
+        self.check_functions()?;
+        let duals: Vec<Dual> = point.iter()
+            .enumerate()
+            .map(|(index, value)| Dual::variable(*value, index, variables.len()))
+            .collect();
+        let sample: Dual = Dual::constant(0.0, variables.len());
+        let mut output: Vec<Dual> = self.evaluate_variables(from_ref(&sample), &|variable| {
+            let index: usize = variables.iter().position(|other| other == variable)?;
+            duals.get(index).map(from_ref)
+        }).map_err(EvaluationError::UnboundVariable)?;
+        Ok (output.remove(0))
+    }
+
+}
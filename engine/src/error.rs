@@ -0,0 +1,192 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Comparison,
+    Expression,
+    Numeric,
+};
+use num::{
+    bigint::BigInt,
+    traits::ToPrimitive,
+};
+use std::{
+    error::Error,
+    f64::consts::E,
+    fmt::{
+        Debug,
+        Display,
+        Formatter,
+        Result as FormatResult,
+    },
+    hash::Hash,
+};
+
+/// An error from evaluating an `Expression`
+#[derive(Clone, Debug, PartialEq)]
+pub enum EvaluationError<I = usize> {
+
+    /// A variable was not given a value
+    UnboundVariable (I),
+
+    /// A function was applied without being expanded with its `Definition`
+    UnexpandedFunction (I),
+
+    /// An integer was too large to convert into a finite number
+    NonFiniteInteger (BigInt),
+
+    /// The number of values given for a point did not match the number of variables
+    MismatchedPoint {
+        /// The number of variables
+        variables: usize,
+        /// The number of values
+        values: usize,
+    },
+
+    /// A sample was outside of the domain of the `Expression`
+    Domain {
+        /// The index of the sample
+        sample: usize,
+        /// The violation of the domain
+        error: DomainError,
+    },
+
+}
+
+/// A violation of the domain of a function
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DomainError {
+
+    /// Division of a number by zero
+    DivisionByZero (f64),
+
+    /// Application of the natural logarithm function to a number that is not positive
+    Logarithm (f64),
+
+    /// Exponentiation with no real result
+    Power {
+        /// The base
+        base: f64,
+        /// The exponent
+        exponent: f64,
+    },
+
+    /// Selection from a piecewise expression where no branch is selected
+    Piecewise,
+
+}
+
+impl<I: Display> Display for EvaluationError<I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        use EvaluationError::*;
+        match self {
+            UnboundVariable (variable) => write!(f, "variable {} has no value", variable),
+            UnexpandedFunction (name) => write!(f, "function {} is not expanded", name),
+            NonFiniteInteger (integer) => write!(f, "integer {} is too large", integer),
+            MismatchedPoint { variables, values } =>
+                write!(f, "{} values were given for {} variables", values, variables),
+            Domain { sample, error } => write!(f, "sample {} is undefined: {}", sample, error),
+        }
+    }
+}
+
+impl<I: Debug + Display> Error for EvaluationError<I> {}
+
+impl Display for DomainError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        use DomainError::*;
+        match self {
+            DivisionByZero (dividend) => write!(f, "{} is divided by zero", dividend),
+            Logarithm (value) => write!(f, "the logarithm of {} is not real", value),
+            Power { base, exponent } => write!(f, "{} ^ {} is not real", base, exponent),
+            Piecewise => write!(f, "no branch of a piecewise expression is selected"),
+        }
+    }
+}
+
+impl Error for DomainError {}
+
+/// Numbers are checked for domain violations, which propagate through every operation
+impl Numeric for Result<f64, DomainError> {
+    fn from_bigint(integer: &BigInt, _: &Self) -> Self {
+        Ok (integer.to_f64().unwrap_or(f64::NAN))
+    }
+    fn add(&self, other: &Self) -> Self { Ok ((*self)? + (*other)?) }
+    fn mul(&self, other: &Self) -> Self { Ok ((*self)? * (*other)?) }
+    fn div(&self, other: &Self) -> Self {
+        let (dividend, divisor): (f64, f64) = ((*self)?, (*other)?);
+        if divisor == 0.0 { Err (DomainError::DivisionByZero (dividend)) } else {
+            Ok (dividend / divisor)
+        }
+    }
+    fn powf(&self, exponent: &Self) -> Self {
+        let (base, exponent): (f64, f64) = ((*self)?, (*exponent)?);
+        if (base < 0.0 && exponent.fract() != 0.0) || (base == 0.0 && exponent < 0.0) {
+            Err (DomainError::Power { base, exponent })
+        } else { Ok (base.powf(exponent)) }
+    }
+    fn exp(&self) -> Self { Ok (E.powf((*self)?)) }
+    fn ln(&self) -> Self {
+        let value: f64 = (*self)?;
+        if value <= 0.0 { Err (DomainError::Logarithm (value)) } else { Ok (value.ln()) }
+    }
+    fn abs(&self) -> Self { Ok ((*self)?.abs()) }
+    fn signum(&self) -> Self {
+        let value: f64 = (*self)?;
+        Ok (if value == 0.0 { 0.0 } else { value.signum() })
+    }
+    fn select(branches: &[(Comparison, Self, Self)], _: &Self) -> Self {
+        for (comparison, compared, value) in branches {
+            if comparison.holds((*compared)?) { return *value }
+        }
+        Err (DomainError::Piecewise)
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Returns an error if an `Expression` contains an integer that cannot be converted into
+    /// a finite `f64`
+    pub(crate) fn check_integers(&self) -> Result<(), EvaluationError<I>> {
+        match self {
+            Expression::Integer (integer) => match integer.to_f64() {
+                Some (float) if float.is_finite() => Ok (()),
+                _ => Err (EvaluationError::NonFiniteInteger (integer.clone())),
+            }
+            expression => expression.children().into_iter()
+                .try_for_each(|child| child.check_integers()),
+        }
+    }
+
+    /// Returns an error if an `Expression` applies a function or a derivative of one, which
+    /// cannot be evaluated without being expanded
+    pub(crate) fn check_functions(&self) -> Result<(), EvaluationError<I>> {
+        match self {
+            Expression::Function (name, _) | Expression::Derivative (name, _, _) =>
+                Err (EvaluationError::UnexpandedFunction (name.clone())),
+            expression => expression.children().into_iter()
+                .try_for_each(|child| child.check_functions()),
+        }
+    }
+
+    /// Evaluates an `Expression` with a list of input values for a given variable, reporting
+    /// the first sample outside of the domain of the `Expression` instead of yielding `NaN`
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`)
+    pub fn evaluate_strict(
+        &self,
+        variable: &I,
+        values: &[f64],
+    ) -> Result<Vec<f64>, EvaluationError<I>> {
+        self.check_integers()?;
+        let values: Vec<Result<f64, DomainError>> = values.iter()
+            .map(|value| Ok (*value))
+            .collect();
+        self.evaluate_with(variable, &values)?.into_iter()
+            .enumerate()
+            .map(|(sample, value)| value
+                .map_err(|error| EvaluationError::Domain { sample, error })
+            )
+            .collect()
+    }
+
+}
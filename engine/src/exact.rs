@@ -0,0 +1,120 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Comparison,
+    Expression,
+};
+use num::{
+    bigint::BigInt,
+    rational::BigRational,
+    traits::{
+        Signed,
+        ToPrimitive,
+    },
+    One,
+    Zero,
+};
+use std::hash::Hash;
+
+/// Returns the exact `n`th root of an integer, if it has one
+fn exact_root(integer: &BigInt, n: u32) -> Option<BigInt> {
+    if integer.is_negative() && n.is_multiple_of(2) { return None }
+    let root: BigInt = integer.nth_root(n);
+    if root.pow(n) == *integer { Some (root) } else { None }
+}
+
+/// The largest number of bits in the numerator or denominator of an exact power
+const MAXIMUM_BITS: u64 = 1 << 20;
+
+/// Raises an integer to a non-negative integer power, or returns `None` if the exponent is
+/// negative or the power is too large to compute
+pub(crate) fn integer_power(base: &BigInt, exponent: &BigInt) -> Option<BigInt> {
+    let exponent: u32 = exponent.to_u32()?;
+    // powers of zero and units stay small for any exponent
+    let bits: u64 = base.bits();
+    if bits > 1 && bits * exponent as u64 > MAXIMUM_BITS { return None }
+    Some (base.pow(exponent))
+}
+
+/// Raises a rational number to an integer power, or returns `None` if zero is raised to a
+/// negative power or the power is too large to compute
+fn exact_power(base: &BigRational, exponent: &BigInt) -> Option<BigRational> {
+    let magnitude: u32 = exponent.magnitude().to_u32()?;
+    // powers of zero and units stay small for any exponent
+    let bits: u64 = base.numer().bits().max(base.denom().bits());
+    if bits > 1 && bits * magnitude as u64 > MAXIMUM_BITS { return None }
+    let power: BigRational = BigRational::new(
+        base.numer().pow(magnitude),
+        base.denom().pow(magnitude)
+    );
+    if exponent.is_negative() {
+        if power.is_zero() { None } else { Some (power.recip()) }
+    } else { Some (power) }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Evaluates an `Expression` exactly at a rational value for a given variable
+    ///
+    /// (This method returns `None` if the result is not rational, if the `Expression` is
+    /// undefined at the value, or if other unsubstituted variables or unexpanded functions
+    /// remain in the `Expression`)
+    pub fn evaluate_exact(&self, variable: &I, value: &BigRational) -> Option<BigRational> {
+        use Expression::*;
+        match self {
+            Sum (terms) => terms.iter().try_fold(BigRational::zero(), |sum, term|
+                Some (sum + term.evaluate_exact(variable, value)?)
+            ),
+            Product (factors) => factors.iter().try_fold(BigRational::one(), |product, factor|
+                Some (product * factor.evaluate_exact(variable, value)?)
+            ),
+            Quotient (operands) => {
+                let dividend: BigRational = operands.0.evaluate_exact(variable, value)?;
+                let divisor: BigRational = operands.1.evaluate_exact(variable, value)?;
+                if divisor.is_zero() { None } else { Some (dividend / divisor) }
+            }
+            Power (operands) => {
+                let base: BigRational = operands.0.evaluate_exact(variable, value)?;
+                let exponent: BigRational = operands.1.evaluate_exact(variable, value)?;
+                if exponent.is_integer() { return exact_power(&base, exponent.numer()) }
+                // fractional powers are only rational if the base has an exact root
+                if base.is_negative() { return None }
+                let degree: u32 = exponent.denom().to_u32()?;
+                let root: BigRational = BigRational::new(
+                    exact_root(base.numer(), degree)?,
+                    exact_root(base.denom(), degree)?,
+                );
+                exact_power(&root, exponent.numer())
+            }
+            Exponential (operand) => {
+                // `e ^ x` is irrational for every rational `x` other than zero
+                let operand: BigRational = operand.evaluate_exact(variable, value)?;
+                if operand.is_zero() { Some (BigRational::one()) } else { None }
+            }
+            Logarithm (operand) => {
+                // `ln(x)` is irrational for every rational `x` other than one
+                let operand: BigRational = operand.evaluate_exact(variable, value)?;
+                if operand.is_one() { Some (BigRational::zero()) } else { None }
+            }
+            Abs (operand) => Some (operand.evaluate_exact(variable, value)?.abs()),
+            Sign (operand) => Some (operand.evaluate_exact(variable, value)?.signum()),
+            Piecewise (branches) => {
+                for (condition, branch) in branches {
+                    let compared: BigRational = condition.expression()
+                        .evaluate_exact(variable, value)?;
+                    let holds: bool = match condition.comparison() {
+                        Comparison::Positive => compared.is_positive(),
+                        Comparison::NonNegative => !compared.is_negative(),
+                        Comparison::NonZero => !compared.is_zero(),
+                    };
+                    if holds { return branch.evaluate_exact(variable, value) }
+                }
+                None
+            }
+            Variable (identifier) if identifier == variable => Some (value.clone()),
+            Variable (_) | Function (..) | Derivative (..) => None,
+            Integer (integer) => Some (BigRational::from_integer(integer.clone())),
+        }
+    }
+
+}
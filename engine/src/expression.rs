@@ -1,13 +1,27 @@
 // Copyright Rob Gage 2025
 
-use crate::Monomial;
+use crate::{
+    exact::integer_power,
+    Assumptions,
+    Comparison,
+    Condition,
+    Definition,
+    Definitions,
+    EvaluationError,
+    Monomial,
+    Numeric,
+    Rule,
+};
 use num::{
     bigint::BigInt,
+    complex::Complex,
     integer::Integer,
-    traits::ToPrimitive,
+    traits::{
+        Signed,
+        ToPrimitive,
+    },
 };
 use std::{
-    f64::consts::E,
     fmt::{
         Display,
         Formatter,
@@ -18,7 +32,8 @@ use std::{
 };
 
 /// An algebraic expression
-#[derive(Clone)]
+#[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expression<I: Clone + Eq + Hash +PartialEq = usize> {
 
     /// Addition of terms
@@ -39,11 +54,33 @@ pub enum Expression<I: Clone + Eq + Hash +PartialEq = usize> {
     /// Application of the natural logarithm function to a term
     Logarithm (Box<Expression<I>>),
 
+    /// Absolute value of a term
+    Abs (Box<Expression<I>>),
+
+    /// Sign of a term, which is `-1`, `0` or `1`
+    Sign (Box<Expression<I>>),
+
+    /// The first of a list of branches whose `Condition` holds, which is undefined where none
+    /// holds
+    Piecewise (Vec<(Condition<I>, Expression<I>)>),
+
+    /// Application of a named function to arguments, which stays symbolic until it is expanded
+    /// with its `Definition`
+    Function (I, Vec<Expression<I>>),
+
+    /// Application of a partial derivative of a named function to arguments, with the indices
+    /// of the arguments that it is differentiated with respect to in ascending order (an index
+    /// is repeated once for each time it is differentiated with respect to that argument)
+    Derivative (I, Vec<usize>, Vec<Expression<I>>),
+
     /// A variable
     Variable (I),
 
     /// An integer
-    Integer (BigInt),
+    Integer (
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::integer"))]
+        BigInt
+    ),
 
 }
 
@@ -51,71 +88,151 @@ impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
 
     /// Evaluates an `Expression` with a list of input values for a given variable
     ///
-    /// (This method requires that no other unsubstituted variables remain in the `Expression`)
-    pub fn evaluate(&self, variable: &I, values: &[f64]) -> Result<Vec<f64>, ()> {
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`,
+    /// and evaluates repeated subexpressions only once)
+    pub fn evaluate(&self, variable: &I, values: &[f64]) -> Result<Vec<f64>, EvaluationError<I>> {
+        self.check_integers()?;
+        self.evaluate_with(variable, values)
+    }
+
+    /// Evaluates an `Expression` with a list of complex input values for a given variable
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`,
+    /// and uses the principal branch of each function)
+    pub fn evaluate_complex(
+        &self,
+        variable: &I,
+        values: &[Complex<f64>],
+    ) -> Result<Vec<Complex<f64>>, EvaluationError<I>> {
+        self.check_integers()?;
+        self.evaluate_with(variable, values)
+    }
+
+    /// Evaluates an `Expression` with a list of `Numeric` input values for a given variable
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`,
+    /// and evaluates repeated subexpressions only once)
+    pub fn evaluate_with<T: Numeric>(
+        &self,
+        variable: &I,
+        values: &[T],
+    ) -> Result<Vec<T>, EvaluationError<I>> {
+        self.check_functions()?;
+        self.eliminate_common_subexpressions().evaluate_with(variable, values)
+    }
+
+    /// Evaluates an `Expression` for a list of samples, looking up the values of each
+    /// variable with a function, or returns the first variable without values
+    pub(crate) fn evaluate_variables<'a, T: Numeric + 'a>(
+        &self,
+        samples: &[T],
+        lookup: &dyn Fn(&I) -> Option<&'a [T]>,
+    ) -> Result<Vec<T>, I> {
+        let constant = |integer: &BigInt| -> Vec<T> { samples.iter()
+            .map(|sample| T::from_bigint(integer, sample))
+            .collect()
+        };
         match self {
             Expression::Sum (terms) => {
-                let mut output: Vec<f64> = vec![0.0; values.len()];
+                let mut output: Vec<T> = constant(&BigInt::ZERO);
                 for term in terms {
-                    let term_values: Vec<f64> = term.evaluate(variable, values)?;
+                    let term_values: Vec<T> = term.evaluate_variables(samples, lookup)?;
                     for (a, b) in output.iter_mut().zip(term_values) {
-                        *a += b;
+                        *a = a.add(&b);
                     }
                 }
                 Ok (output)
             }
             Expression::Product (factors) => {
-                let mut output: Vec<f64> = vec![1.0; values.len()];
+                let mut output: Vec<T> = constant(&BigInt::from(1));
                 for factor in factors {
-                    let term_values: Vec<f64> = factor.evaluate(variable, values)?;
+                    let term_values: Vec<T> = factor.evaluate_variables(samples, lookup)?;
                     for (a, b) in output.iter_mut().zip(term_values) {
-                        *a *= b;
+                        *a = a.mul(&b);
                     }
                 }
                 Ok (output)
             }
             Expression::Quotient (operands) => Ok (
-                operands.0.evaluate(variable, values)?.into_iter()
-                    .zip(operands.1.evaluate(variable, values)?.into_iter())
-                    .map(|(a, b)| a / b)
+                operands.0.evaluate_variables(samples, lookup)?.into_iter()
+                    .zip(operands.1.evaluate_variables(samples, lookup)?)
+                    .map(|(a, b)| a.div(&b))
                     .collect()
             ),
             Expression::Power (operands) => Ok (
-                operands.0.evaluate(variable, values)?.into_iter()
-                    .zip(operands.1.evaluate(variable, values)?.into_iter())
-                    .map(|(a, b)| a.powf(b))
+                operands.0.evaluate_variables(samples, lookup)?.into_iter()
+                    .zip(operands.1.evaluate_variables(samples, lookup)?)
+                    .map(|(a, b)| a.powf(&b))
                     .collect()
             ),
             Expression::Exponential (operand) => Ok (
-                operand.evaluate(variable, values)?.into_iter()
-                    .map(|value| E.powf(value))
+                operand.evaluate_variables(samples, lookup)?.into_iter()
+                    .map(|value| value.exp())
                     .collect()
             ),
             Expression::Logarithm (operand) => Ok (
-                operand.evaluate(variable, values)?.into_iter()
+                operand.evaluate_variables(samples, lookup)?.into_iter()
                     .map(|value| value.ln())
                     .collect()
             ),
-            Expression::Variable (identifier) if identifier == variable => Ok (values.to_vec()),
-            Expression::Integer (integer) => {
-                let float: f64 = integer.to_f64().unwrap_or(f64::NAN);
-                Ok (vec![float; values.len()])
+            Expression::Abs (operand) => Ok (
+                operand.evaluate_variables(samples, lookup)?.into_iter()
+                    .map(|value| value.abs())
+                    .collect()
+            ),
+            Expression::Sign (operand) => Ok (
+                operand.evaluate_variables(samples, lookup)?.into_iter()
+                    .map(|value| value.signum())
+                    .collect()
+            ),
+            Expression::Piecewise (branches) => {
+                // every branch is evaluated, and a branch is selected for each sample
+                let mut evaluated: Vec<(Comparison, Vec<T>, Vec<T>)> = Vec::new();
+                for (condition, branch) in branches {
+                    evaluated.push((
+                        condition.comparison(),
+                        condition.expression().evaluate_variables(samples, lookup)?,
+                        branch.evaluate_variables(samples, lookup)?,
+                    ));
+                }
+                Ok (samples.iter()
+                    .enumerate()
+                    .map(|(index, sample)| {
+                        let selectable: Vec<(Comparison, T, T)> = evaluated.iter()
+                            .map(|(comparison, conditions, values)| {
+                                (*comparison, conditions[index].clone(), values[index].clone())
+                            })
+                            .collect();
+                        T::select(&selectable, sample)
+                    })
+                    .collect()
+                )
+            }
+            Expression::Variable (identifier) => match lookup(identifier) {
+                Some (values) => Ok (values.to_vec()),
+                None => Err (identifier.clone()),
             }
-            _ => Err (())
+            Expression::Function (name, _) | Expression::Derivative (name, _, _) =>
+                Err (name.clone()),
+            Expression::Integer (integer) => Ok (constant(integer)),
         }
     }
 
     /// Reduce an `Expression`, or returns it unchanged if not reducible
-    pub fn reduce(self) -> Self {
+    pub fn reduce(self) -> Self { self.reduce_with(&Assumptions::new()) }
+
+    /// Reduce an `Expression` with rewrites that are only valid under some `Assumptions`, or
+    /// returns it unchanged if not reducible
+    pub fn reduce_with(self, assumptions: &Assumptions<I>) -> Self {
         use Expression::*;
         match self {
             Sum(terms) => {
                 let terms: Vec<Self> = terms.into_iter()
                     .flat_map(|term| match term {
                         Sum(terms) => terms.into_iter()
-                            .map(|term| term.reduce())
+                            .map(|term| term.reduce_with(assumptions))
                             .collect(),
-                        other => vec![other.reduce()]
+                        other => vec![other.reduce_with(assumptions)]
                     })
                     .collect();
                 match terms {
@@ -141,15 +258,22 @@ impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
                 }
             }
             Product(factors) => {
+                let factors: Vec<Self> = factors.into_iter()
+                    .map(|factor| factor.reduce_with(assumptions))
+                    .collect();
                 let monomial: Monomial<I> = Monomial::from_factors(&factors);
                 monomial.to_expression()
             }
             Quotient(terms) => {
-                let dividend: Expression<I> = terms.0.reduce();
-                let divisor: Expression<I> = terms.1.reduce();
+                let dividend: Expression<I> = terms.0.reduce_with(assumptions);
+                let divisor: Expression<I> = terms.1.reduce_with(assumptions);
                 match (&dividend, &divisor) {
-                    // reduce fractions
-                    (Integer (numerator), Integer (denominator)) => {
+                    // x / x = 1 for nonzero x
+                    _ if dividend == divisor && assumptions.is_nonzero(&divisor) =>
+                        Integer (BigInt::from(1)),
+                    // reduce fractions (leaving division by zero undefined)
+                    (Integer (numerator), Integer (denominator))
+                    if denominator != &BigInt::ZERO => {
                         let gcd: BigInt = numerator.gcd(&denominator);
                         let numerator: BigInt = numerator / &gcd;
                         let denominator: BigInt = denominator / &gcd;
@@ -161,41 +285,172 @@ impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
                 }
             }
             Power (terms) => {
-                let base: Expression<I> = terms.0.reduce();
-                let exponent: Expression<I> = terms.1.reduce();
+                let base: Expression<I> = terms.0.reduce_with(assumptions);
+                let exponent: Expression<I> = terms.1.reduce_with(assumptions);
                 match (&base, &exponent) {
+                    // powers too large to compute are left unevaluated
                     (Integer (base), Integer (exponent))
-                    if exponent <= &BigInt::from(u32::MAX)
-                    && exponent >= &BigInt::ZERO =>
-                        Integer (base.pow(exponent.to_u32_digits().1[0])),
+                        if let Some (power) = integer_power(base, exponent) => Integer (power),
+                    // x ^ 0 = 1 for nonzero x
+                    (_, Integer (integer)) if integer == &BigInt::ZERO
+                        && assumptions.is_nonzero(&base) => Integer (BigInt::from(1)),
+                    // (x ^ a) ^ b = x ^ (a * b) for positive x, or nonzero x and integers a and b
+                    (Power (operands), _) if (assumptions.is_positive(&operands.0)
+                        && assumptions.is_real(&operands.1)
+                        && assumptions.is_real(&exponent))
+                        || (assumptions.is_nonzero(&operands.0)
+                        && matches!((&operands.1, &exponent), (Integer (_), Integer (_)))) => {
+                        let (base, inner): (Expression<I>, Expression<I>) = (**operands).clone();
+                        match Product (vec![inner, exponent]).reduce_with(assumptions) {
+                            Integer (integer) if integer == BigInt::from(1) => base,
+                            exponent => Power (Box::new((base, exponent))),
+                        }
+                    }
                     _ => Power (Box::new((base, exponent)))
                 }
             }
+            Exponential (operand) => match operand.reduce_with(assumptions) {
+                // e ^ ln(x) = x for positive x
+                Logarithm (operand) if assumptions.is_positive(&operand) => *operand,
+                operand => Exponential (Box::new(operand)),
+            }
+            Logarithm (operand) => match operand.reduce_with(assumptions) {
+                // ln(e ^ x) = x for real x
+                Exponential (operand) if assumptions.is_real(&operand) => *operand,
+                // ln(x ^ a) = a * ln(x) for positive x and real a
+                Power (operands) if assumptions.is_positive(&operands.0)
+                    && assumptions.is_real(&operands.1) => {
+                    let (base, exponent): (Expression<I>, Expression<I>) = *operands;
+                    Product (vec![exponent, Logarithm (Box::new(base))]).reduce_with(assumptions)
+                }
+                operand => Logarithm (Box::new(operand)),
+            }
+            Function (name, arguments) => Function (name, arguments.into_iter()
+                .map(|argument| argument.reduce_with(assumptions))
+                .collect()
+            ),
+            Derivative (name, indices, arguments) => {
+                let arguments: Vec<Self> = arguments.into_iter()
+                    .map(|argument| argument.reduce_with(assumptions))
+                    .collect();
+                Derivative (name, indices, arguments)
+            }
+            Abs (operand) => match operand.reduce_with(assumptions) {
+                Integer (integer) => Integer (integer.abs()),
+                // |x| = x for positive x
+                operand if assumptions.is_positive(&operand) => operand,
+                // ||x|| = |x|
+                Abs (operand) => Abs (operand),
+                operand => Abs (Box::new(operand)),
+            }
+            Sign (operand) => match operand.reduce_with(assumptions) {
+                Integer (integer) => Integer (integer.signum()),
+                // sign(x) = 1 for positive x
+                operand if assumptions.is_positive(&operand) => Integer (BigInt::from(1)),
+                operand => Sign (Box::new(operand)),
+            }
+            Piecewise (branches) => {
+                let mut reduced: Vec<(Condition<I>, Self)> = Vec::new();
+                for (condition, branch) in branches {
+                    let condition: Condition<I> = condition
+                        .map(|expression| expression.reduce_with(assumptions));
+                    // branches with integer conditions are always or never selected
+                    match condition.expression() {
+                        Integer (integer) if condition.comparison()
+                            .holds(integer.to_f64().unwrap_or(f64::NAN)) => {
+                            let branch: Self = branch.reduce_with(assumptions);
+                            if reduced.is_empty() { return branch }
+                            reduced.push((condition, branch));
+                            break
+                        }
+                        Integer (_) => {}
+                        _ => reduced.push((condition, branch.reduce_with(assumptions))),
+                    }
+                }
+                Piecewise (reduced)
+            }
             other => other
         }
     }
 
-    /// Differentiates this `Expression` with respect to a variable
+    /// Differentiates this `Expression` with respect to a variable, keeping the derivatives of
+    /// the functions it applies symbolic
     pub fn differentiate(&self, variable: &I) -> Self {
+        self.differentiate_with(variable, &Definitions::new())
+    }
+
+    /// Differentiates this `Expression` with respect to a variable, applying the chain rule
+    /// through the `Definitions` of the functions it applies
+    ///
+    /// (Derivatives of undefined functions, and of functions applied within their own
+    /// definitions, are kept symbolic)
+    pub fn differentiate_with(&self, variable: &I, definitions: &Definitions<I>) -> Self {
+        self.differentiate_expanding(variable, definitions, &[])
+    }
+
+    /// Differentiates this `Expression` with respect to a variable within the `Definitions` of
+    /// some functions, whose applications are not differentiated through their definitions
+    /// again
+    pub(crate) fn differentiate_expanding(
+        &self,
+        variable: &I,
+        definitions: &Definitions<I>,
+        expanding: &[I],
+    ) -> Self {
+        self.apply_rule(
+            variable,
+            definitions,
+            expanding,
+            &mut |operand| operand.differentiate_expanding(variable, definitions, expanding),
+        ).1
+    }
+
+    /// Applies the `Rule` of differentiation that matches this `Expression`, differentiating
+    /// its operands with a function, and returns the `Rule` with the derivative
+    ///
+    /// (The function is called at most once for each operand, and applications of the
+    /// functions being expanded are differentiated without their definitions)
+    pub(crate) fn apply_rule(
+        &self,
+        variable: &I,
+        definitions: &Definitions<I>,
+        expanding: &[I],
+        derive: &mut dyn FnMut(&Self) -> Self,
+    ) -> (Rule, Self) {
         use Expression::*;
         match self {
-            // identity rule
-            Variable (identifier) if identifier == variable => Integer (BigInt::from(1)),
-            // variable rule
-            Variable (_) => Integer (BigInt::from(0)),
-            // constant rule
-            Integer (_) => Integer (BigInt::from(0)),
-            // sum rule
-            Sum(terms) => Sum(terms.iter()
-                .map(|operand| operand.differentiate(variable))
+            Variable (identifier) if identifier == variable =>
+                (Rule::Identity, Integer (BigInt::from(1))),
+            Variable (_) => (Rule::Variable, Integer (BigInt::from(0))),
+            // functions with definitions may depend on the variable through their bodies
+            Function (name, arguments) | Derivative (name, _, arguments)
+                if definitions.get(name).is_some() || self.contains(variable) => {
+                let indices: &[usize] = match self {
+                    Derivative (_, indices, _) => indices,
+                    _ => &[],
+                };
+                (Rule::Chain, Self::chain(
+                    name,
+                    indices,
+                    arguments,
+                    variable,
+                    definitions,
+                    expanding,
+                    derive,
+                ))
+            }
+            // terms without the variable are constant, even where `ln` of them is undefined
+            expression if !expression.contains(variable) =>
+                (Rule::Constant, Integer (BigInt::from(0))),
+            Sum(terms) => (Rule::Sum, Sum(terms.iter()
+                .map(&mut *derive)
                 .collect()
-            ),
-            // product rule
-            Product (factors) => Sum(factors.iter()
+            )),
+            Product (factors) => (Rule::Product, Sum(factors.iter()
                 .enumerate()
                 .map(|(factor_index, factor)| {
                     let mut output: Vec<Expression<I>> = Vec::with_capacity(factors.len());
-                    output.push(factor.differentiate(variable));
+                    output.push(derive(factor));
                     for index in 0..factors.len() {
                         if index != factor_index {
                             output.push(factors[index].clone());
@@ -204,91 +459,163 @@ impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
                     Product(output)
                 })
                 .collect()
-            ),
-            // quotient rule
-            Quotient (terms) => Quotient(Box::new((
+            )),
+            Quotient (terms) => (Rule::Quotient, Quotient(Box::new((
                 Sum(vec![
-                    Product(vec![terms.0.differentiate(variable), terms.1.clone()]),
+                    Product(vec![derive(&terms.0), terms.1.clone()]),
                     Product(vec![
                         terms.0.clone(),
-                        terms.1.differentiate(variable),
+                        derive(&terms.1),
                         Integer (BigInt::from(-1))
                     ]),
                 ]),
                 Product(vec![terms.1.clone(), terms.1.clone()])
-            ))),
-            // power rules
+            )))),
+            Function (..) | Derivative (..) =>
+                unreachable!("applications are differentiated above"),
+            Integer (_) => unreachable!("integers do not contain variables"),
             Power (terms) => match *terms.clone() {
-                // known base shortcut
-                (Integer (base), exponent) => Product(vec![
-                    Power (Box::new((Integer (base.clone()), exponent.clone()))),
-                    Logarithm (Box::new(Integer (base))),
-                    exponent.differentiate(variable)
-                ]),
-                // known exponent shortcut
-                (base, Integer (exponent)) => if exponent == BigInt::ZERO {
-                    Integer (BigInt::ZERO)
-                } else if exponent == BigInt::from(1) {
-                    base.differentiate(variable)
-                } else { Product(vec![
-                    Integer (exponent.clone()),
-                    Power (Box::new ((base.clone(), Integer (exponent - 1)))),
-                    base.differentiate(variable)
-                ])},
-                // general power rule
-                (base, exponent) => Product(vec![
+                (base, exponent) if !base.contains(variable) => {
+                    let derivative: Expression<I> = Product(vec![
+                        Power (Box::new((base.clone(), exponent.clone()))),
+                        Logarithm (Box::new(base)),
+                        derive(&exponent)
+                    ]);
+                    (Rule::ConstantBase, derivative)
+                }
+                (base, Integer (exponent)) => {
+                    let derivative: Expression<I> = if exponent == BigInt::ZERO {
+                        Integer (BigInt::ZERO)
+                    } else if exponent == BigInt::from(1) {
+                        derive(&base)
+                    } else { Product(vec![
+                        Integer (exponent.clone()),
+                        Power (Box::new ((base.clone(), Integer (exponent - 1)))),
+                        derive(&base)
+                    ])};
+                    (Rule::ConstantExponent, derivative)
+                }
+                (base, exponent) if !exponent.contains(variable) => {
+                    let reduced: Expression<I> = Sum (vec![
+                        exponent.clone(),
+                        Integer (BigInt::from(-1)),
+                    ]);
+                    (Rule::ConstantExponent, Product(vec![
+                        exponent,
+                        Power (Box::new((base.clone(), reduced))),
+                        derive(&base)
+                    ]))
+                }
+                (base, exponent) => (Rule::Power, Product(vec![
                     Power (Box::new((base.clone(), exponent.clone()))),
                     Sum(vec![
                         Product(vec![
-                            exponent.differentiate(variable),
+                            derive(&exponent),
                             Logarithm (Box::new(base.clone()))
                         ]),
                         Product(vec![
                             exponent,
-                            Quotient(Box::new((base.differentiate(variable), base)))
+                            Quotient(Box::new((derive(&base), base)))
                         ])
                     ])
-                ])
+                ]))
             }
-            // exponential rule
-            Exponential (term) => Product(vec![
+            Exponential (term) => (Rule::Exponential, Product(vec![
                 Exponential (term.clone()),
-                term.differentiate(variable)
-            ]),
-            // logarithm rule
-            Logarithm (term) => Quotient(Box::new((
-                term.differentiate(variable),
+                derive(term)
+            ])),
+            Logarithm (term) => (Rule::Logarithm, Quotient(Box::new((
+                derive(term),
                 *term.clone(),
-            ))),
+            )))),
+            // the derivatives of `Abs`, `Sign` and `Piecewise` are undefined at their kinks
+            Abs (term) => {
+                let derivative: Expression<I> = derive(term);
+                let negated = |expression: Expression<I>| Product (vec![
+                    Integer (BigInt::from(-1)),
+                    expression,
+                ]);
+                (Rule::Abs, Piecewise (vec![
+                    (Condition::Positive (*term.clone()), derivative.clone()),
+                    (Condition::Positive (negated(*term.clone())), negated(derivative)),
+                ]))
+            }
+            Sign (term) => (Rule::Sign, Piecewise (vec![
+                (Condition::NonZero (*term.clone()), Integer (BigInt::from(0))),
+            ])),
+            // each branch is undefined on the boundaries of its condition and earlier ones
+            Piecewise (branches) => {
+                let mut boundaries: Vec<Expression<I>> = Vec::new();
+                let mut derivatives: Vec<(Condition<I>, Expression<I>)> = Vec::new();
+                for (condition, branch) in branches {
+                    boundaries.push(condition.expression().clone());
+                    let boundary: Expression<I> = match boundaries.as_slice() {
+                        [boundary] => boundary.clone(),
+                        boundaries => Product (boundaries.to_vec()),
+                    };
+                    derivatives.push((condition.clone(), Piecewise (vec![
+                        (Condition::NonZero (boundary), derive(branch)),
+                    ])));
+                }
+                (Rule::Piecewise, Piecewise (derivatives))
+            }
         }
     }
 
-}
 
-impl Display for Expression<String> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+    /// Applies the chain rule to the application of a function, or of a partial derivative of
+    /// it with respect to the arguments at some indices, differentiating the arguments with a
+    /// function
+    ///
+    /// (The partial derivatives of functions without a matching `Definition`, or that are being
+    /// expanded, are kept symbolic)
+    fn chain(
+        name: &I,
+        indices: &[usize],
+        arguments: &[Self],
+        variable: &I,
+        definitions: &Definitions<I>,
+        expanding: &[I],
+        derive: &mut dyn FnMut(&Self) -> Self,
+    ) -> Self {
         use Expression::*;
-        match self {
-            Sum(terms) => {
-                for index in 0..terms.len() {
-                    if index != 0 { f.write_str(" + ")?; }
-                    write!(f, "{}", terms[index])?;
-                }
-                Ok(())
+        let definition: Option<&Definition<I>> = definitions.get(name)
+            .filter(|definition| !expanding.contains(name)
+                && definition.parameters().len() == arguments.len()
+                && indices.iter().all(|index| *index < arguments.len()));
+        // the body of the definition is differentiated within its expansion
+        let expanding: Vec<I> = expanding.iter().chain([name]).cloned().collect();
+        let partial = |index: usize| {
+            let mut indices: Vec<usize> = indices.to_vec();
+            indices.push(index);
+            indices.sort_unstable();
+            match definition {
+                Some (definition) => definition.substitute(
+                    definition.partial_expanding(&indices, definitions, &expanding),
+                    arguments,
+                ),
+                None => Derivative (name.clone(), indices, arguments.to_vec()),
             }
-            Product (factors) => {
-                let monomial: Monomial<String> = Monomial::from_factors(factors);
-                write!(f, "{}", monomial)
-            }
-            Quotient(operands) => write!(
-                f,
-                "\\displaystyle \\frac{{{}}}{{{}}}",
-                operands.0,operands.1),
-            Power (operands) => write!(f, "{}^{{{}}}", operands.0, operands.1),
-            Exponential (operand) => write!(f, "e^{{{}}}", operand),
-            Logarithm (operand) => write!(f, "\\ln({})", operand),
-            Variable (name) => write!(f, "\\mathit{{{}}}", name),
-            Integer (integer) => f.write_str(&integer.to_string()),
+        };
+        let mut terms: Vec<Self> = arguments.iter()
+            .enumerate()
+            .map(|(index, argument)| Product (vec![partial(index), derive(argument)]))
+            .collect();
+        // the body of a definition may also contain the variable itself
+        if let Some (definition) = definition.filter(|definition| !definition.parameters()
+            .contains(variable)
+        ) {
+            terms.push(definition.substitute(
+                definition.partial_expanding(indices, definitions, &expanding)
+                    .differentiate_expanding(variable, definitions, &expanding),
+                arguments,
+            ));
         }
+        Sum (terms)
     }
-}
\ No newline at end of file
+
+}
+
+impl Display for Expression<String> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult { write!(f, "{}", self.latex()) }
+}
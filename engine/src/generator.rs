@@ -0,0 +1,232 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Comparison,
+    Condition,
+    Expression,
+    Operation,
+};
+use num::bigint::BigInt;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+};
+
+/// A seeded generator of random `Expression`s, which generates the same sequence of
+/// `Expression`s for the same seed and configuration
+pub struct Generator<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The state of the pseudorandom number generator
+    state: u64,
+    /// The variables that may appear in generated expressions
+    variables: Vec<I>,
+    /// The names and numbers of arguments of functions that may be applied in generated
+    /// expressions
+    functions: Vec<(I, usize)>,
+    /// The maximum depth of operations in generated expressions
+    depth: usize,
+    /// The relative weight of each operation
+    weights: HashMap<Operation, u32>,
+    /// The relative weight of ending a branch early with a variable or an integer
+    leaf_weight: u32,
+    /// The smallest and largest integers that may appear in generated expressions
+    integers: (i64, i64),
+    /// The largest number of operands of generated sums and products
+    operands: usize,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Generator<I> {
+
+    /// Creates a new `Generator` with a seed and the variables that may appear in generated
+    /// `Expression`s
+    ///
+    /// (By default, expressions are at most four operations deep, every operation is equally
+    /// likely, no functions are applied, integers are between -5 and 5, and sums, products and
+    /// piecewise expressions have up to three operands or branches)
+    pub fn new(seed: u64, variables: Vec<I>) -> Self {
+        use Operation::*;
+        Self {
+            state: seed,
+            variables,
+            functions: Vec::new(),
+            depth: 4,
+            weights: [
+                Sum,
+                Product,
+                Quotient,
+                Power,
+                Exponential,
+                Logarithm,
+                Abs,
+                Sign,
+                Piecewise,
+                Function,
+                Derivative,
+            ]
+                .into_iter()
+                .map(|operation| (operation, 1))
+                .collect(),
+            leaf_weight: 1,
+            integers: (-5, 5),
+            operands: 3,
+        }
+    }
+
+    /// Returns the `Generator` with a maximum depth of operations
+    pub fn depth(self, depth: usize) -> Self { Self { depth, ..self } }
+
+    /// Returns the `Generator` with the names and numbers of arguments, which are at least one,
+    /// of functions that may be applied, or whose partial derivatives may be applied
+    pub fn functions(self, functions: Vec<(I, usize)>) -> Self { Self { functions, ..self } }
+
+    /// Returns the `Generator` with a relative weight for an `Operation`, where a weight of
+    /// zero disables it
+    ///
+    /// (Applications of functions and their derivatives are only generated if there are
+    /// functions to apply)
+    pub fn weight(mut self, operation: Operation, weight: u32) -> Self {
+        self.weights.insert(operation, weight);
+        self
+    }
+
+    /// Returns the `Generator` with a relative weight for ending a branch early with a
+    /// variable or an integer
+    pub fn leaf_weight(self, leaf_weight: u32) -> Self { Self { leaf_weight, ..self } }
+
+    /// Returns the `Generator` with the smallest and largest integers that may be generated
+    pub fn integers(self, minimum: i64, maximum: i64) -> Self {
+        Self { integers: (minimum, maximum.max(minimum)), ..self }
+    }
+
+    /// Returns the `Generator` with the largest number of operands of sums and products, which
+    /// is at least two, and of branches of piecewise expressions
+    pub fn operands(self, operands: usize) -> Self {
+        Self { operands: operands.max(2), ..self }
+    }
+
+    /// Generates a random `Expression`
+    pub fn generate(&mut self) -> Expression<I> { self.expression(self.depth) }
+
+    /// Generates a random `Expression` with at most a number of operations above each leaf
+    fn expression(&mut self, depth: usize) -> Expression<I> {
+        use Expression::*;
+        let mut choices: Vec<(Option<Operation>, u32)> = Vec::new();
+        if depth > 0 {
+            // operations are chosen in a fixed order, so that the results depend on the seed
+            for operation in [
+                Operation::Sum,
+                Operation::Product,
+                Operation::Quotient,
+                Operation::Power,
+                Operation::Exponential,
+                Operation::Logarithm,
+                Operation::Abs,
+                Operation::Sign,
+                Operation::Piecewise,
+                Operation::Function,
+                Operation::Derivative,
+            ] {
+                let applies: bool =
+                    matches!(operation, Operation::Function | Operation::Derivative);
+                let weight: u32 = if applies && self.functions.is_empty() { 0 } else {
+                    self.weights.get(&operation).copied().unwrap_or(0)
+                };
+                choices.push((Some (operation), weight));
+            }
+        }
+        choices.push((None, if depth == 0 { 1 } else { self.leaf_weight }));
+        let total: u64 = choices.iter().map(|(_, weight)| *weight as u64).sum();
+        let mut choice: u64 = if total == 0 { 0 } else { self.below(total) };
+        let mut operation: Option<Operation> = None;
+        for (option, weight) in choices {
+            if choice < weight as u64 {
+                operation = option;
+                break
+            }
+            choice -= weight as u64;
+        }
+        match operation {
+            Some (Operation::Sum) => Sum (self.terms(depth - 1)),
+            Some (Operation::Product) => Product (self.terms(depth - 1)),
+            Some (Operation::Quotient) => Quotient (Box::new((
+                self.expression(depth - 1),
+                self.expression(depth - 1),
+            ))),
+            Some (Operation::Power) => Power (Box::new((
+                self.expression(depth - 1),
+                self.expression(depth - 1),
+            ))),
+            Some (Operation::Exponential) => Exponential (Box::new(self.expression(depth - 1))),
+            Some (Operation::Logarithm) => Logarithm (Box::new(self.expression(depth - 1))),
+            Some (Operation::Abs) => Abs (Box::new(self.expression(depth - 1))),
+            Some (Operation::Sign) => Sign (Box::new(self.expression(depth - 1))),
+            Some (Operation::Piecewise) => {
+                let count: usize = 1 + self.below(self.operands as u64) as usize;
+                Piecewise ((0..count).map(|_| {
+                    let comparison: Comparison = [
+                        Comparison::Positive,
+                        Comparison::NonNegative,
+                        Comparison::NonZero,
+                    ][self.below(3) as usize];
+                    let condition: Condition<I> = Condition::new(
+                        comparison,
+                        self.expression(depth - 1),
+                    );
+                    (condition, self.expression(depth - 1))
+                }).collect())
+            }
+            Some (Operation::Function) => {
+                let (name, arguments): (I, Vec<Expression<I>>) = self.application(depth - 1);
+                Function (name, arguments)
+            }
+            Some (Operation::Derivative) => {
+                let (name, arguments): (I, Vec<Expression<I>>) = self.application(depth - 1);
+                let order: usize = 1 + self.below(3) as usize;
+                let mut indices: Vec<usize> = (0..order)
+                    .map(|_| self.below(arguments.len() as u64) as usize)
+                    .collect();
+                indices.sort_unstable();
+                Derivative (name, indices, arguments)
+            }
+            None => self.leaf(),
+        }
+    }
+
+    /// Generates the name and random arguments of an application of a function
+    fn application(&mut self, depth: usize) -> (I, Vec<Expression<I>>) {
+        let index: usize = self.below(self.functions.len() as u64) as usize;
+        let (name, count): (I, usize) = self.functions[index].clone();
+        (name, (0..count.max(1)).map(|_| self.expression(depth)).collect())
+    }
+
+    /// Generates the random operands of a sum or product
+    fn terms(&mut self, depth: usize) -> Vec<Expression<I>> {
+        let count: usize = 2 + self.below(self.operands as u64 - 1) as usize;
+        (0..count).map(|_| self.expression(depth)).collect()
+    }
+
+    /// Generates a random variable or integer
+    fn leaf(&mut self) -> Expression<I> {
+        if !self.variables.is_empty() && self.below(2) == 0 {
+            let index: usize = self.below(self.variables.len() as u64) as usize;
+            Expression::Variable (self.variables[index].clone())
+        } else {
+            let (minimum, maximum): (i64, i64) = self.integers;
+            let range: u128 = (maximum as i128 - minimum as i128 + 1) as u128;
+            let offset: u128 = self.next_integer() as u128 % range;
+            Expression::Integer (BigInt::from(minimum as i128 + offset as i128))
+        }
+    }
+
+    /// Returns a pseudorandom integer below a bound, which must not be zero
+    fn below(&mut self, bound: u64) -> u64 { self.next_integer() % bound }
+
+    /// Returns the next pseudorandom integer of the SplitMix64 sequence
+    fn next_integer(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z: u64 = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+}
@@ -0,0 +1,311 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Decimal,
+    Domain,
+    EvaluationError,
+    Expression,
+    Generator,
+};
+use num::rational::BigRational;
+use std::{
+    error::Error,
+    fmt::{
+        Debug,
+        Display,
+        Formatter,
+        Result as FormatResult,
+    },
+    hash::Hash,
+    slice::from_ref,
+};
+
+/// A property of an `Expression` that a `Harness` found not to hold
+#[derive(Clone, Debug, PartialEq)]
+pub enum Failure<I = usize> {
+
+    /// The derivative differs from a central finite difference at a point
+    Derivative { point: f64, expected: f64, actual: f64 },
+
+    /// A derivative from automatic differentiation differs from the symbolic derivative at a
+    /// point
+    AutomaticDerivative { point: f64, expected: f64, actual: f64 },
+
+    /// The reduced expression differs from the original at a point
+    Reduction { point: f64, expected: f64, actual: f64 },
+
+    /// The printed expression was not parsed back as the same expression
+    RoundTrip { text: String },
+
+    /// The expression could not be evaluated
+    Evaluation (EvaluationError<I>),
+
+}
+
+impl<I: Display> Display for Failure<I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        use Failure::*;
+        match self {
+            Derivative { point, expected, actual } => write!(
+                f,
+                "derivative is {} at {}, but the finite difference is {}",
+                actual, point, expected
+            ),
+            AutomaticDerivative { point, expected, actual } => write!(
+                f,
+                "automatic derivative is {} at {}, but the symbolic derivative is {}",
+                actual, point, expected
+            ),
+            Reduction { point, expected, actual } => write!(
+                f,
+                "reduced expression is {} at {}, but the original is {}",
+                actual, point, expected
+            ),
+            RoundTrip { text } => write!(f, "`{}` is not parsed back as the same expression", text),
+            Evaluation (error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl<I: Debug + Display> Error for Failure<I> {}
+
+impl<I> From<EvaluationError<I>> for Failure<I> {
+    fn from(error: EvaluationError<I>) -> Self { Failure::Evaluation (error) }
+}
+
+/// The number of significant digits used to confirm differences between floating-point values
+const PRECISION: u32 = 30;
+
+/// Checks properties of `Expression`s numerically at sample points, such as generated
+/// `Expression`s of a single variable
+///
+/// (Points where an `Expression` is undefined, or too steep for finite differences to be
+/// accurate, are skipped)
+#[derive(Clone, Debug)]
+pub struct Harness {
+    /// The points at which expressions are evaluated
+    points: Vec<f64>,
+    /// The step of finite differences, relative to the magnitude of the point
+    step: f64,
+    /// The largest relative difference between values that are considered equal
+    tolerance: f64,
+}
+
+impl Harness {
+
+    /// Creates a new `Harness` that samples points between -3 and 3 that are not integers
+    pub fn new() -> Self {
+        Self {
+            points: (-6..6).map(|index| index as f64 / 2.0 + 0.237).collect(),
+            step: 1e-4,
+            tolerance: 1e-4,
+        }
+    }
+
+    /// Returns the `Harness` with the points at which expressions are evaluated
+    pub fn points(self, points: Vec<f64>) -> Self { Self { points, ..self } }
+
+    /// Returns the `Harness` with the largest relative difference between values that are
+    /// considered equal
+    pub fn tolerance(self, tolerance: f64) -> Self { Self { tolerance, ..self } }
+
+    /// Checks that the derivative of an `Expression` agrees with central finite differences
+    /// of it
+    ///
+    /// (Points where the finite difference is inaccurate are skipped, but a derivative that is
+    /// undefined where the finite difference is accurate fails)
+    pub fn check_derivative<I: Clone + Eq + Hash + PartialEq>(
+        &self,
+        expression: &Expression<I>,
+        variable: &I,
+    ) -> Result<(), Failure<I>> {
+        let domain: Domain<I> = expression.domain(variable);
+        let derivative: Vec<f64> = expression.differentiate(variable)
+            .evaluate(variable, &self.points)?;
+        for (point, actual) in self.points.iter().zip(derivative) {
+            if let Some (expected) = self.difference(expression, &domain, variable, *point)?
+                && !(actual.is_finite() && self.close(expected, actual)) {
+                return Err (Failure::Derivative { point: *point, expected, actual })
+            }
+        }
+        Ok (())
+    }
+
+    /// Checks that forward-mode and reverse-mode automatic differentiation of an `Expression`
+    /// agree with its symbolic derivative
+    ///
+    /// (Points where finite differences are inaccurate are skipped, since the symbolic and
+    /// automatic derivatives may then both be inaccurate in different ways)
+    pub fn check_automatic_derivative<I: Clone + Eq + Hash + PartialEq>(
+        &self,
+        expression: &Expression<I>,
+        variable: &I,
+    ) -> Result<(), Failure<I>> {
+        let symbolic: Vec<f64> = expression.differentiate(variable)
+            .evaluate(variable, &self.points)?;
+        let forward: Vec<f64> = expression.evaluate_derivative(variable, &self.points)?;
+        let domain: Domain<I> = expression.domain(variable);
+        for ((point, expected), forward) in self.points.iter().zip(symbolic).zip(forward) {
+            if !expected.is_finite()
+                || self.difference(expression, &domain, variable, *point)?.is_none() {
+                continue
+            }
+            let (_, reverse): (f64, Vec<f64>) = expression.gradient(
+                from_ref(variable),
+                &[*point],
+            )?;
+            for actual in [forward, reverse[0]] {
+                if !self.close(expected, actual) {
+                    return Err (Failure::AutomaticDerivative { point: *point, expected, actual })
+                }
+            }
+        }
+        Ok (())
+    }
+
+    /// Checks that the reduced form of an `Expression` has the same value wherever the
+    /// `Expression` is defined
+    ///
+    /// (Differences are confirmed with `Decimal` evaluation, since rounding in floating-point
+    /// evaluation of the original, such as underflow or signed zeros, may be what differs)
+    pub fn check_reduce<I: Clone + Eq + Hash + PartialEq>(
+        &self,
+        expression: &Expression<I>,
+        variable: &I,
+    ) -> Result<(), Failure<I>> {
+        let reduced: Expression<I> = expression.clone().reduce();
+        let original: Vec<f64> = expression.evaluate(variable, &self.points)?;
+        let values: Vec<f64> = reduced.evaluate(variable, &self.points)?;
+        for ((point, expected), actual) in self.points.iter().zip(original).zip(values) {
+            if !expected.is_finite() || self.close(expected, actual) { continue }
+            let Some (rational) = BigRational::from_float(*point) else { continue };
+            let Some (precise) = expression.evaluate_decimal(variable, &rational, PRECISION)
+            else { continue };
+            let close: bool = reduced.evaluate_decimal(variable, &rational, PRECISION)
+                .is_some_and(|value: Decimal| self.close(precise.to_f64(), value.to_f64()));
+            if !close { return Err (Failure::Reduction { point: *point, expected, actual }) }
+        }
+        Ok (())
+    }
+
+    /// Checks that the plain-text rendering of an `Expression` is read back as the same
+    /// `Expression` by a parser
+    pub fn check_round_trip<I: Clone + Display + Eq + Hash + PartialEq>(
+        &self,
+        expression: &Expression<I>,
+        parse: impl Fn(&str) -> Option<Expression<I>>,
+    ) -> Result<(), Failure<I>> {
+        let text: String = expression.plain_text().to_string();
+        match parse(&text) {
+            Some (parsed) if &parsed == expression => Ok (()),
+            _ => Err (Failure::RoundTrip { text }),
+        }
+    }
+
+    /// Checks the symbolic and automatic derivatives, reduced forms and plain-text round trips
+    /// of a number of `Expression`s from a `Generator`, and returns the first `Expression` that
+    /// fails with its `Failure`
+    pub fn run<I: Clone + Display + Eq + Hash + PartialEq>(
+        &self,
+        generator: &mut Generator<I>,
+        variable: &I,
+        count: usize,
+        parse: impl Fn(&str) -> Option<Expression<I>>,
+    ) -> Result<(), (Expression<I>, Failure<I>)> {
+        for _ in 0..count {
+            let expression: Expression<I> = generator.generate();
+            self.check_derivative(&expression, variable)
+                .and_then(|_| self.check_automatic_derivative(&expression, variable))
+                .and_then(|_| self.check_reduce(&expression, variable))
+                .and_then(|_| self.check_round_trip(&expression, &parse))
+                .map_err(|failure| (expression, failure))?;
+        }
+        Ok (())
+    }
+
+    /// Returns a central finite difference of an `Expression` at a point, or `None` if it is not
+    /// accurate enough to compare with derivatives
+    ///
+    /// (Differences are not taken outside of the `Domain` of the `Expression`, where
+    /// floating-point evaluation may pass through infinities to finite values, such as
+    /// `exp(x / 0)`, nor at points that are `degenerate`)
+    fn difference<I: Clone + Eq + Hash + PartialEq>(
+        &self,
+        expression: &Expression<I>,
+        domain: &Domain<I>,
+        variable: &I,
+        point: f64,
+    ) -> Result<Option<f64>, Failure<I>> {
+        let step: f64 = self.step * point.abs().max(1.0);
+        for sample in [point - step, point, point + step] {
+            if !domain.contains(sample)? { return Ok (None) }
+        }
+        if self.degenerate(expression, variable, point)? { return Ok (None) }
+        let values: Vec<f64> = expression.evaluate(
+            variable,
+            &[point - step, point + step, point - step / 2.0, point + step / 2.0],
+        )?;
+        let coarse: f64 = (values[1] - values[0]) / (2.0 * step);
+        let fine: f64 = (values[3] - values[2]) / step;
+        // differences that change with the step are not accurate enough to compare
+        if !coarse.is_finite() || !fine.is_finite() || !self.close(coarse, fine) {
+            return Ok (None)
+        }
+        // nor are differences of values so large that rounding hides the change
+        let magnitude: f64 = values.iter().fold(0.0, |maximum, value| value.abs().max(maximum));
+        if magnitude * f64::EPSILON / step > self.tolerance * fine.abs().max(1.0) {
+            return Ok (None)
+        }
+        Ok (Some (fine))
+    }
+
+    /// Returns `true` if an `Expression` or any part of it is infinite at a point, if the
+    /// operand of an `Abs` or `Sign`, or the expression of a `Condition`, is exactly zero
+    /// there, or if the point is outside of the `Domain` of a selected branch
+    ///
+    /// (Whether a derivative exists at such a kink depends on more than the point, such as for
+    /// `sign(0 * x)` and `sign(x^2)` at zero, and the `Domain` of a `Piecewise` expression
+    /// leaves out its branches)
+    fn degenerate<I: Clone + Eq + Hash + PartialEq>(
+        &self,
+        expression: &Expression<I>,
+        variable: &I,
+        point: f64,
+    ) -> Result<bool, Failure<I>> {
+        use Expression::*;
+        let kinks: Vec<&Expression<I>> = match expression {
+            Abs (operand) | Sign (operand) => vec![operand],
+            Piecewise (branches) => branches.iter()
+                .map(|(condition, _)| condition.expression())
+                .collect(),
+            _ => vec![],
+        };
+        for kink in kinks {
+            if kink.evaluate(variable, &[point])?[0] == 0.0 { return Ok (true) }
+        }
+        if let Piecewise (branches) = expression {
+            for (condition, branch) in branches {
+                if condition.holds(variable, point)? {
+                    if !branch.domain(variable).contains(point)? { return Ok (true) }
+                    break
+                }
+            }
+        }
+        if expression.evaluate(variable, &[point])?[0].is_infinite() { return Ok (true) }
+        for child in expression.children() {
+            if self.degenerate(child, variable, point)? { return Ok (true) }
+        }
+        Ok (false)
+    }
+
+    /// Returns `true` if two values are equal within the tolerance, relative to the larger of
+    /// their magnitudes and one
+    fn close(&self, a: f64, b: f64) -> bool {
+        (a - b).abs() <= self.tolerance * a.abs().max(b.abs()).max(1.0)
+    }
+
+}
+
+impl Default for Harness {
+    fn default() -> Self { Self::new() }
+}
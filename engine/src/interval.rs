@@ -0,0 +1,273 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Comparison,
+    EvaluationError,
+    Expression,
+    Numeric,
+};
+use num::{
+    bigint::BigInt,
+    traits::ToPrimitive,
+};
+use std::{
+    hash::Hash,
+    ops::{
+        Add,
+        Div,
+        Mul,
+        Neg,
+    },
+};
+
+/// A closed interval of real numbers, possibly unbounded or empty
+///
+/// (Results of operations on `Interval`s are rounded outwards, so that they always enclose
+/// every possible result of the operation on members of the operands)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    /// The lower bound of the `Interval`
+    minimum: f64,
+    /// The upper bound of the `Interval`
+    maximum: f64,
+}
+
+impl Interval {
+
+    /// The `Interval` containing every real number
+    pub const ENTIRE: Self = Self { minimum: f64::NEG_INFINITY, maximum: f64::INFINITY };
+
+    /// The `Interval` containing no numbers
+    pub const EMPTY: Self = Self { minimum: f64::INFINITY, maximum: f64::NEG_INFINITY };
+
+    /// Creates a new `Interval` from its bounds, or an empty `Interval` if the bounds are out of
+    /// order or not numbers
+    pub fn new(minimum: f64, maximum: f64) -> Self {
+        if minimum <= maximum { Self { minimum, maximum } } else { Self::EMPTY }
+    }
+
+    /// Creates an `Interval` containing a single number
+    pub fn point(value: f64) -> Self { Self::new(value, value) }
+
+    /// Returns the lower bound of the `Interval`
+    pub fn minimum(&self) -> f64 { self.minimum }
+
+    /// Returns the upper bound of the `Interval`
+    pub fn maximum(&self) -> f64 { self.maximum }
+
+    /// Returns the width of the `Interval`
+    pub fn width(&self) -> f64 {
+        if self.is_empty() { 0.0 } else { self.maximum - self.minimum }
+    }
+
+    /// Returns `true` if the `Interval` contains no numbers
+    pub fn is_empty(&self) -> bool { self.minimum > self.maximum }
+
+    /// Returns `true` if the `Interval` is non-empty and has finite bounds
+    pub fn is_bounded(&self) -> bool {
+        !self.is_empty() && self.minimum.is_finite() && self.maximum.is_finite()
+    }
+
+    /// Returns `true` if the `Interval` contains a number
+    pub fn contains(&self, value: f64) -> bool {
+        self.minimum <= value && value <= self.maximum
+    }
+
+    /// Returns the intersection of two `Interval`s
+    pub fn intersection(self, other: Self) -> Self {
+        Self::new(self.minimum.max(other.minimum), self.maximum.min(other.maximum))
+    }
+
+    /// Returns the smallest `Interval` containing two `Interval`s
+    pub fn hull(self, other: Self) -> Self {
+        if self.is_empty() { return other }
+        if other.is_empty() { return self }
+        Self::new(self.minimum.min(other.minimum), self.maximum.max(other.maximum))
+    }
+
+    /// Creates an `Interval` from bounds computed with rounding errors, widening it by one
+    /// unit in the last place in each direction
+    fn outward(minimum: f64, maximum: f64) -> Self {
+        Self::new(minimum.next_down(), maximum.next_up())
+    }
+
+    /// Creates an `Interval` from bounds computed with a relative error of at most a number of
+    /// machine epsilons, widening it by that error and then outward
+    fn widened(minimum: f64, maximum: f64, epsilons: f64) -> Self {
+        let error = |bound: f64| if bound.is_finite() {
+            bound.abs() * epsilons * f64::EPSILON
+        } else { 0.0 };
+        Self::outward(minimum - error(minimum), maximum + error(maximum))
+    }
+
+    /// Returns the smallest `Interval` containing a number of possible bounds, ignoring those
+    /// that are not numbers
+    fn enclosing(bounds: &[f64]) -> Self {
+        let minimum: f64 = bounds.iter().copied().fold(f64::INFINITY, f64::min);
+        let maximum: f64 = bounds.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Self::outward(minimum, maximum)
+    }
+
+    /// Returns the reciprocal of the `Interval`
+    pub fn reciprocal(self) -> Self {
+        if self.is_empty() || (self.minimum == 0.0 && self.maximum == 0.0) { return Self::EMPTY }
+        if self.minimum == 0.0 { return Self::outward(1.0 / self.maximum, f64::INFINITY) }
+        if self.maximum == 0.0 { return Self::outward(f64::NEG_INFINITY, 1.0 / self.minimum) }
+        if self.contains(0.0) { return Self::ENTIRE }
+        Self::enclosing(&[1.0 / self.maximum, 1.0 / self.minimum])
+    }
+
+    /// Applies the exponential function to the `Interval`
+    pub fn exp(self) -> Self {
+        if self.is_empty() { return Self::EMPTY }
+        // unlike `E.powf`, whose error grows with the magnitude of the exponent, `f64::exp` is
+        // accurate to within one unit in the last place
+        Self::widened(self.minimum.exp(), self.maximum.exp(), 1.0)
+            .intersection(Self::new(0.0, f64::INFINITY))
+    }
+
+    /// Applies the natural logarithm function to the `Interval`, ignoring the numbers outside
+    /// of its domain
+    pub fn ln(self) -> Self {
+        let domain: Self = self.intersection(Self::new(0.0, f64::INFINITY));
+        if domain.is_empty() || domain.maximum == 0.0 { return Self::EMPTY }
+        Self::outward(domain.minimum.ln(), domain.maximum.ln())
+    }
+
+    /// Raises the `Interval` to an integer power
+    pub fn powi(self, exponent: i32) -> Self {
+        if self.is_empty() { return Self::EMPTY }
+        if exponent == 0 { return Self::point(1.0) }
+        if exponent < 0 { return match exponent.checked_neg() {
+            Some (exponent) => self.powi(exponent).reciprocal(),
+            None => Self::ENTIRE,
+        }}
+        // repeated multiplication rounds at most once for each factor
+        let epsilons: f64 = exponent as f64;
+        if exponent % 2 == 1 {
+            // odd powers are increasing
+            Self::widened(self.minimum.powi(exponent), self.maximum.powi(exponent), epsilons)
+        } else if self.contains(0.0) {
+            // even powers have a minimum at zero
+            let maximum: f64 = self.minimum.abs().max(self.maximum.abs());
+            Self::widened(0.0, maximum.powi(exponent), epsilons)
+                .intersection(Self::new(0.0, f64::INFINITY))
+        } else {
+            let magnitudes: (f64, f64) = (self.minimum.abs(), self.maximum.abs());
+            Self::widened(
+                magnitudes.0.min(magnitudes.1).powi(exponent),
+                magnitudes.0.max(magnitudes.1).powi(exponent),
+                epsilons,
+            ).intersection(Self::new(0.0, f64::INFINITY))
+        }
+    }
+
+    /// Raises the `Interval` to the power of another, ignoring negative bases unless the
+    /// exponent is a single integer
+    pub fn powf(self, exponent: Self) -> Self {
+        if self.is_empty() || exponent.is_empty() { return Self::EMPTY }
+        if exponent.minimum == exponent.maximum
+            && exponent.minimum.fract() == 0.0
+            && exponent.minimum.abs() <= i32::MAX as f64 {
+            return self.powi(exponent.minimum as i32)
+        }
+        let base: Self = self.intersection(Self::new(0.0, f64::INFINITY));
+        if base.is_empty() { return Self::EMPTY }
+        // x ^ y is monotonic in both x and y over each quadrant, so its extrema are at corners
+        Self::enclosing(&[
+            base.minimum.powf(exponent.minimum),
+            base.minimum.powf(exponent.maximum),
+            base.maximum.powf(exponent.minimum),
+            base.maximum.powf(exponent.maximum),
+        ])
+    }
+
+}
+
+impl Add for Interval {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        if self.is_empty() || other.is_empty() { return Self::EMPTY }
+        Self::outward(self.minimum + other.minimum, self.maximum + other.maximum)
+    }
+}
+
+impl Mul for Interval {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        if self.is_empty() || other.is_empty() { return Self::EMPTY }
+        // bounds are limits of finite numbers, so zero times an infinite bound is zero
+        let product = |a: f64, b: f64| if a == 0.0 || b == 0.0 { 0.0 } else { a * b };
+        Self::enclosing(&[
+            product(self.minimum, other.minimum),
+            product(self.minimum, other.maximum),
+            product(self.maximum, other.minimum),
+            product(self.maximum, other.maximum),
+        ])
+    }
+}
+
+impl Div for Interval {
+    type Output = Self;
+    fn div(self, other: Self) -> Self { Mul::mul(self, other.reciprocal()) }
+}
+
+impl Neg for Interval {
+    type Output = Self;
+    fn neg(self) -> Self { Self { minimum: -self.maximum, maximum: -self.minimum } }
+}
+
+impl Numeric for Interval {
+    fn from_bigint(integer: &BigInt, _: &Self) -> Self {
+        match integer.to_f64() {
+            // integers up to 2 ^ 53 are represented exactly
+            Some (float) if float.abs() <= 9007199254740992.0 => Interval::point(float),
+            Some (float) if float.is_finite() => Interval::outward(float, float),
+            _ => Interval::ENTIRE,
+        }
+    }
+    fn add(&self, other: &Self) -> Self { *self + *other }
+    fn mul(&self, other: &Self) -> Self { *self * *other }
+    fn div(&self, other: &Self) -> Self { *self / *other }
+    fn powf(&self, exponent: &Self) -> Self { Interval::powf(*self, *exponent) }
+    fn exp(&self) -> Self { Interval::exp(*self) }
+    fn ln(&self) -> Self { Interval::ln(*self) }
+    fn abs(&self) -> Self {
+        if self.is_empty() || self.minimum >= 0.0 { *self } else if self.maximum <= 0.0 {
+            -*self
+        } else { Interval::new(0.0, self.maximum.max(-self.minimum)) }
+    }
+    fn signum(&self) -> Self {
+        if self.is_empty() { return *self }
+        let sign = |value: f64| if value == 0.0 { 0.0 } else { value.signum() };
+        Interval::new(sign(self.minimum), sign(self.maximum))
+    }
+    // every branch that may be selected somewhere in the `Interval` is enclosed
+    fn select(branches: &[(Comparison, Self, Self)], _: &Self) -> Self {
+        let mut enclosure: Interval = Interval::EMPTY;
+        for (comparison, compared, value) in branches {
+            match comparison.decide(*compared) {
+                Some (true) => return enclosure.hull(*value),
+                Some (false) => {}
+                None => enclosure = enclosure.hull(*value),
+            }
+        }
+        enclosure
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Evaluates an `Expression` over an `Interval` of values for a given variable, returning
+    /// an `Interval` that encloses every value of the `Expression` in its domain
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`)
+    pub fn evaluate_interval(
+        &self,
+        variable: &I,
+        input: Interval,
+    ) -> Result<Interval, EvaluationError<I>> {
+        Ok (self.evaluate_with(variable, &[input])?[0])
+    }
+
+}
@@ -0,0 +1,209 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Expression,
+    Visitor,
+};
+use num::{
+    bigint::BigInt,
+    Signed,
+    Zero,
+};
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    hash::Hash,
+};
+
+/// An operation that combines the children of an `Expression`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Operation {
+
+    /// Addition of terms
+    Sum,
+
+    /// Multiplication of factors
+    Product,
+
+    /// Division of a term by another
+    Quotient,
+
+    /// Exponentiation of a term to another as a power
+    Power,
+
+    /// Application of the exponential function
+    Exponential,
+
+    /// Application of the natural logarithm function
+    Logarithm,
+
+    /// Absolute value
+    Abs,
+
+    /// Sign
+    Sign,
+
+    /// Selection of a branch by conditions
+    Piecewise,
+
+    /// Application of a named function
+    Function,
+
+    /// Application of a partial derivative of a named function
+    Derivative,
+
+}
+
+/// The variables of an `Expression` in order of first appearance
+struct Variables<'a, I: Clone + Eq + Hash + PartialEq> {
+    /// The variables in order of first appearance
+    variables: Vec<&'a I>,
+    /// The variables found so far
+    found: HashSet<&'a I>,
+}
+
+impl<'a, I: Clone + Eq + Hash + PartialEq> Variables<'a, I> {
+
+    /// Collects the variables of an `Expression`
+    fn collect(&mut self, expression: &'a Expression<I>) {
+        match expression {
+            Expression::Variable (variable) => if self.found.insert(variable) {
+                self.variables.push(variable);
+            }
+            expression => for child in expression.children() { self.collect(child) }
+        }
+    }
+
+}
+
+/// A `Visitor` that counts the nodes of each `Operation`
+struct Operations {
+    /// The number of nodes of each operation
+    counts: HashMap<Operation, usize>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Visitor<I> for Operations {
+    fn visit(&mut self, expression: &Expression<I>) {
+        if let Some (operation) = expression.operation() {
+            *self.counts.entry(operation).or_default() += 1;
+        }
+        self.visit_children(expression)
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Returns the `Operation` that combines the children of an `Expression`, or `None` if it
+    /// is a variable or an integer
+    pub fn operation(&self) -> Option<Operation> {
+        use Expression::*;
+        match self {
+            Sum (_) => Some (Operation::Sum),
+            Product (_) => Some (Operation::Product),
+            Quotient (_) => Some (Operation::Quotient),
+            Power (_) => Some (Operation::Power),
+            Exponential (_) => Some (Operation::Exponential),
+            Logarithm (_) => Some (Operation::Logarithm),
+            Abs (_) => Some (Operation::Abs),
+            Sign (_) => Some (Operation::Sign),
+            Piecewise (_) => Some (Operation::Piecewise),
+            Function (..) => Some (Operation::Function),
+            Derivative (..) => Some (Operation::Derivative),
+            Variable (_) | Integer (_) => None,
+        }
+    }
+
+    /// Returns the variables of an `Expression` without repetition, in order of first
+    /// appearance
+    pub fn free_variables(&self) -> Vec<I> {
+        let mut variables: Variables<I> = Variables {
+            variables: Vec::new(),
+            found: HashSet::new(),
+        };
+        variables.collect(self);
+        variables.variables.into_iter().cloned().collect()
+    }
+
+    /// Returns `true` if an `Expression` contains a variable
+    pub fn contains(&self, variable: &I) -> bool {
+        match self {
+            Expression::Variable (other) => other == variable,
+            expression => expression.children().into_iter()
+                .any(|child| child.contains(variable)),
+        }
+    }
+
+    /// Returns the number of nodes in an `Expression`, counting every operation, variable and
+    /// integer
+    pub fn node_count(&self) -> usize {
+        1 + self.children().into_iter().map(Expression::node_count).sum::<usize>()
+    }
+
+    /// Returns the number of nodes on the longest path from the root of an `Expression` to a
+    /// variable or an integer, which is one for a variable or an integer
+    pub fn depth(&self) -> usize {
+        1 + self.children().into_iter().map(Expression::depth).max().unwrap_or(0)
+    }
+
+    /// Returns the number of nodes of each `Operation` in an `Expression`
+    ///
+    /// (Operations that do not occur are omitted)
+    pub fn operation_counts(&self) -> HashMap<Operation, usize> {
+        let mut operations: Operations = Operations { counts: HashMap::new() };
+        operations.visit(self);
+        operations.counts
+    }
+
+    /// Returns `true` if an `Expression` is a polynomial in a variable, with coefficients that
+    /// do not contain it
+    pub fn is_polynomial_in(&self, variable: &I) -> bool { self.degree_in(variable).is_some() }
+
+    /// Returns `true` if an `Expression` is a quotient of polynomials in a variable, with
+    /// coefficients that do not contain it
+    pub fn is_rational_in(&self, variable: &I) -> bool {
+        use Expression::*;
+        if !self.contains(variable) { return true }
+        match self {
+            Sum (operands) | Product (operands) => operands.iter()
+                .all(|operand| operand.is_rational_in(variable)),
+            Quotient (operands) => operands.0.is_rational_in(variable)
+                && operands.1.is_rational_in(variable),
+            Power (operands) => matches!(&operands.1, Integer (_))
+                && operands.0.is_rational_in(variable),
+            Exponential (_) | Logarithm (_) | Abs (_) | Sign (_) | Piecewise (_) | Function (..)
+            | Derivative (..) => false,
+            Variable (_) | Integer (_) => true,
+        }
+    }
+
+    /// Returns the degree of an `Expression` in a variable if it is a polynomial in it, or
+    /// `None` otherwise
+    ///
+    /// (The degree is read from the structure of the `Expression`, so terms that cancel each
+    /// other out are still counted)
+    pub fn degree_in(&self, variable: &I) -> Option<BigInt> {
+        use Expression::*;
+        if !self.contains(variable) { return Some (BigInt::zero()) }
+        match self {
+            Sum (terms) => terms.iter()
+                .map(|term| term.degree_in(variable))
+                .try_fold(BigInt::zero(), |maximum, degree| Some (maximum.max(degree?))),
+            Product (factors) => factors.iter()
+                .map(|factor| factor.degree_in(variable))
+                .sum(),
+            Quotient (operands) if !operands.1.contains(variable) =>
+                operands.0.degree_in(variable),
+            Power (operands) => match &operands.1 {
+                Integer (exponent) if !exponent.is_negative() =>
+                    Some (operands.0.degree_in(variable)? * exponent),
+                _ => None,
+            }
+            Variable (_) => Some (BigInt::from(1)),
+            Quotient (_) | Exponential (_) | Logarithm (_) | Abs (_) | Sign (_) | Piecewise (_)
+            | Function (..) | Derivative (..) | Integer (_) => None,
+        }
+    }
+
+}
@@ -0,0 +1,241 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    notation::{
+        is_number,
+        needs_parentheses,
+        negation,
+        root_index,
+        Context,
+    },
+    Comparison,
+    Expression,
+};
+use num::bigint::BigInt;
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as FormatResult,
+        Write,
+    },
+    hash::Hash,
+};
+
+/// The style in which quotients are rendered
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FractionStyle {
+
+    /// Quotients are stacked with `\frac`
+    #[default]
+    Stacked,
+
+    /// Quotients are written on one line with a slash
+    Inline,
+
+}
+
+/// A LaTeX rendering of an `Expression`, with operator precedence, subtraction and unary minus
+pub struct Latex<'a, I: Clone + Eq + Hash + PartialEq> {
+    /// The rendered `Expression`
+    expression: &'a Expression<I>,
+    /// The style in which quotients are rendered
+    fraction_style: FractionStyle,
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Latex<'_, I> {
+
+    /// Returns the `Latex` with quotients rendered in a `FractionStyle`
+    pub fn fraction_style(self, fraction_style: FractionStyle) -> Self {
+        Self { fraction_style, ..self }
+    }
+
+    /// Writes an `Expression` in a `Context`
+    fn write<W: Write>(
+        &self,
+        w: &mut W,
+        expression: &Expression<I>,
+        context: Context,
+    ) -> FormatResult {
+        use Expression::*;
+        let inline: bool = self.fraction_style == FractionStyle::Inline;
+        if let Some (positive) = negation(expression) {
+            let parenthesized: bool = needs_parentheses(expression, context, inline);
+            if parenthesized { w.write_str("\\left(")? }
+            w.write_char('-')?;
+            self.write(w, &positive, Context::Factor { first: false })?;
+            if parenthesized { w.write_str("\\right)")? }
+            return Ok (())
+        }
+        if needs_parentheses(expression, context, inline) {
+            w.write_str("\\left(")?;
+            self.write(w, expression, Context::Whole)?;
+            return w.write_str("\\right)")
+        }
+        match expression {
+            Sum (terms) => match terms.split_first() {
+                Some ((first, rest)) => {
+                    self.write(w, first, context)?;
+                    for term in rest {
+                        match negation(term) {
+                            Some (positive) => {
+                                w.write_str(" - ")?;
+                                self.write(w, &positive, Context::Term)?;
+                            }
+                            None => {
+                                // sums are associative, unless they begin with a minus sign
+                                let context: Context = match term {
+                                    Sum (terms) if terms.first()
+                                        .is_some_and(|term| negation(term).is_none()) =>
+                                        Context::Whole,
+                                    _ => Context::Term,
+                                };
+                                w.write_str(" + ")?;
+                                self.write(w, term, context)?;
+                            }
+                        }
+                    }
+                    Ok (())
+                }
+                None => w.write_char('0'),
+            }
+            Product (factors) => {
+                // numbers are written before the other factors
+                let mut ordered: Vec<&Expression<I>> = factors.iter()
+                    .filter(|factor| is_number(factor))
+                    .collect();
+                ordered.extend(factors.iter().filter(|factor| !is_number(factor)));
+                if ordered.len() == 1 { return self.write(w, ordered[0], context) }
+                if ordered.is_empty() { return w.write_char('1') }
+                let mut previous: Option<&Expression<I>> = None;
+                for factor in ordered {
+                    let mut rendered: String = String::new();
+                    self.write(&mut rendered, factor, Context::Factor {
+                        first: previous.is_none()
+                    })?;
+                    // juxtaposition would run digits together or read as a mixed number
+                    if previous.is_some_and(|previous| {
+                        rendered.starts_with(|c: char| c.is_ascii_digit())
+                            || (is_number(previous) && matches!(factor, Quotient (_))
+                                && self.fraction_style == FractionStyle::Stacked)
+                    }) {
+                        w.write_str(" \\cdot ")?;
+                    }
+                    w.write_str(&rendered)?;
+                    previous = Some (factor);
+                }
+                Ok (())
+            }
+            Quotient (operands) => match self.fraction_style {
+                FractionStyle::Stacked => {
+                    w.write_str("\\frac{")?;
+                    self.write(w, &operands.0, Context::Whole)?;
+                    w.write_str("}{")?;
+                    self.write(w, &operands.1, Context::Whole)?;
+                    w.write_char('}')
+                }
+                FractionStyle::Inline => {
+                    self.write(w, &operands.0, Context::Factor { first: true })?;
+                    w.write_str(" / ")?;
+                    self.write(w, &operands.1, Context::Divisor)
+                }
+            }
+            Power (operands) => match root_index(&operands.1) {
+                Some (index) => {
+                    if index == BigInt::from(2) { w.write_str("\\sqrt{")? } else {
+                        write!(w, "\\sqrt[{}]{{", index)?
+                    }
+                    self.write(w, &operands.0, Context::Whole)?;
+                    w.write_char('}')
+                }
+                None => {
+                    self.write(w, &operands.0, Context::Base)?;
+                    w.write_str("^{")?;
+                    self.write(w, &operands.1, Context::Whole)?;
+                    w.write_char('}')
+                }
+            }
+            Exponential (operand) => {
+                w.write_str("e^{")?;
+                self.write(w, operand, Context::Whole)?;
+                w.write_char('}')
+            }
+            Logarithm (operand) => {
+                w.write_str("\\ln\\left(")?;
+                self.write(w, operand, Context::Whole)?;
+                w.write_str("\\right)")
+            }
+            Abs (operand) => {
+                w.write_str("\\left|")?;
+                self.write(w, operand, Context::Whole)?;
+                w.write_str("\\right|")
+            }
+            Sign (operand) => {
+                w.write_str("\\operatorname{sgn}\\left(")?;
+                self.write(w, operand, Context::Whole)?;
+                w.write_str("\\right)")
+            }
+            Piecewise (branches) => {
+                w.write_str("\\begin{cases}")?;
+                for (index, (condition, branch)) in branches.iter().enumerate() {
+                    if index > 0 { w.write_str(" \\\\ ")? }
+                    self.write(w, branch, Context::Whole)?;
+                    w.write_str(" & ")?;
+                    self.write(w, condition.expression(), Context::Whole)?;
+                    w.write_str(match condition.comparison() {
+                        Comparison::Positive => " > 0",
+                        Comparison::NonNegative => " \\geq 0",
+                        Comparison::NonZero => " \\neq 0",
+                    })?;
+                }
+                w.write_str("\\end{cases}")
+            }
+            Function (name, arguments) => {
+                write!(w, "\\mathit{{{}}}", name)?;
+                self.arguments(w, arguments)
+            }
+            Derivative (name, indices, arguments) if arguments.len() == 1 => {
+                match indices.len() {
+                    order @ 1..=3 => write!(w, "\\mathit{{{}}}{}", name, "'".repeat(order))?,
+                    order => write!(w, "\\mathit{{{}}}^{{({})}}", name, order)?,
+                }
+                self.arguments(w, arguments)
+            }
+            // partial derivatives are written with the 1-based indices of their arguments
+            Derivative (name, indices, arguments) => {
+                for index in indices { write!(w, "\\partial_{{{}}}", index + 1)? }
+                write!(w, "\\mathit{{{}}}", name)?;
+                self.arguments(w, arguments)
+            }
+            Variable (name) => write!(w, "\\mathit{{{}}}", name),
+            Integer (integer) => write!(w, "{}", integer),
+        }
+    }
+
+    /// Writes the parenthesized arguments of an application
+    fn arguments<W: Write>(&self, w: &mut W, arguments: &[Expression<I>]) -> FormatResult {
+        w.write_str("\\left(")?;
+        for (index, argument) in arguments.iter().enumerate() {
+            if index > 0 { w.write_str(", ")? }
+            self.write(w, argument, Context::Whole)?;
+        }
+        w.write_str("\\right)")
+    }
+
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Display for Latex<'_, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        self.write(f, self.expression, Context::Whole)
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Returns a LaTeX rendering of an `Expression`, with stacked fractions unless another
+    /// `FractionStyle` is chosen
+    pub fn latex(&self) -> Latex<'_, I> {
+        Latex { expression: self, fraction_style: FractionStyle::default() }
+    }
+
+}
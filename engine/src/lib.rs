@@ -1,10 +1,101 @@
 // Copyright Rob Gage 2025
 
+mod assumptions;
+mod bindings;
+mod codegen;
+mod dag;
+mod decimal;
+mod definitions;
+mod domain;
+mod dual;
+mod error;
+mod exact;
 mod expression;
+mod generator;
+mod harness;
+mod interval;
+mod introspection;
+mod latex;
+mod mathml;
 mod namespace;
 mod monomial;
+mod notation;
+mod numeric;
+mod pretty;
+mod program;
+#[cfg(feature = "serde")]
+mod serialization;
+mod steps;
+mod tape;
+mod text;
+mod visit;
 
 use monomial::Monomial;
 
+pub use assumptions::{
+    Assumption,
+    Assumptions,
+};
+pub use bindings::{
+    Bindings,
+    Symbol,
+};
+pub use codegen::{
+    Codegen,
+    Language,
+};
+pub use dag::{
+    Dag,
+    Node,
+};
+pub use decimal::Decimal;
+pub use definitions::{
+    Definition,
+    Definitions,
+};
+pub use domain::{
+    Comparison,
+    Condition,
+    Domain,
+};
+pub use dual::Dual;
+pub use error::{
+    DomainError,
+    EvaluationError,
+};
 pub use expression::Expression;
+pub use generator::Generator;
+pub use harness::{
+    Failure,
+    Harness,
+};
+pub use interval::Interval;
+pub use introspection::Operation;
+pub use latex::{
+    FractionStyle,
+    Latex,
+};
+pub use mathml::{
+    ContentMathMl,
+    MathMl,
+};
+pub use namespace::Namespace;
+pub use numeric::Numeric;
+pub use pretty::Pretty;
+pub use program::{
+    Instruction,
+    Program,
+};
+pub use steps::{
+    Derivation,
+    Rule,
+    Step,
+};
+pub use tape::Tape;
+pub use text::PlainText;
+pub use visit::{
+    Folder,
+    Visitor,
+    VisitorMut,
+};
 pub type Syntax = Expression<String>;
\ No newline at end of file
@@ -0,0 +1,407 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    notation::{
+        is_number,
+        needs_parentheses,
+        negation,
+        root_index,
+        Context,
+    },
+    Comparison,
+    Expression,
+    FractionStyle,
+};
+use num::bigint::BigInt;
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as FormatResult,
+        Write,
+    },
+    hash::Hash,
+};
+
+/// The namespace of MathML elements
+const NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// A Presentation MathML rendering of an `Expression`, with operator precedence, subtraction
+/// and unary minus
+pub struct MathMl<'a, I: Clone + Eq + Hash + PartialEq> {
+    /// The rendered `Expression`
+    expression: &'a Expression<I>,
+    /// The style in which quotients are rendered
+    fraction_style: FractionStyle,
+    /// Whether the `<math>` element is displayed as a block rather than inline with text
+    block: bool,
+}
+
+/// A Content MathML rendering of an `Expression`, which encodes its structure rather than its
+/// appearance
+pub struct ContentMathMl<'a, I: Clone + Eq + Hash + PartialEq> {
+    /// The rendered `Expression`
+    expression: &'a Expression<I>,
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> MathMl<'_, I> {
+
+    /// Returns the `MathMl` with quotients rendered in a `FractionStyle`
+    pub fn fraction_style(self, fraction_style: FractionStyle) -> Self {
+        Self { fraction_style, ..self }
+    }
+
+    /// Returns the `MathMl` displayed as a block if `block` is `true`, or inline with text
+    /// otherwise
+    pub fn block(self, block: bool) -> Self { Self { block, ..self } }
+
+    /// Writes an `Expression` in a `Context` as a single MathML element
+    fn write<W: Write>(
+        &self,
+        w: &mut W,
+        expression: &Expression<I>,
+        context: Context,
+    ) -> FormatResult {
+        use Expression::*;
+        let inline: bool = self.fraction_style == FractionStyle::Inline;
+        if let Some (positive) = negation(expression) {
+            let parenthesized: bool = needs_parentheses(expression, context, inline);
+            w.write_str("<mrow>")?;
+            if parenthesized { w.write_str("<mo>(</mo>")? }
+            w.write_str("<mo>&#x2212;</mo>")?;
+            self.write(w, &positive, Context::Factor { first: false })?;
+            if parenthesized { w.write_str("<mo>)</mo>")? }
+            return w.write_str("</mrow>")
+        }
+        if needs_parentheses(expression, context, inline) {
+            w.write_str("<mrow><mo>(</mo>")?;
+            self.write(w, expression, Context::Whole)?;
+            return w.write_str("<mo>)</mo></mrow>")
+        }
+        match expression {
+            Sum (terms) => match terms.split_first() {
+                Some ((first, [])) => self.write(w, first, context),
+                Some ((first, rest)) => {
+                    w.write_str("<mrow>")?;
+                    self.write(w, first, context)?;
+                    for term in rest {
+                        match negation(term) {
+                            Some (positive) => {
+                                w.write_str("<mo>&#x2212;</mo>")?;
+                                self.write(w, &positive, Context::Term)?;
+                            }
+                            None => {
+                                // sums are associative, unless they begin with a minus sign
+                                let context: Context = match term {
+                                    Sum (terms) if terms.first()
+                                        .is_some_and(|term| negation(term).is_none()) =>
+                                        Context::Whole,
+                                    _ => Context::Term,
+                                };
+                                w.write_str("<mo>+</mo>")?;
+                                self.write(w, term, context)?;
+                            }
+                        }
+                    }
+                    w.write_str("</mrow>")
+                }
+                None => w.write_str("<mn>0</mn>"),
+            }
+            Product (factors) => {
+                // numbers are written before the other factors
+                let mut ordered: Vec<&Expression<I>> = factors.iter()
+                    .filter(|factor| is_number(factor))
+                    .collect();
+                ordered.extend(factors.iter().filter(|factor| !is_number(factor)));
+                if ordered.len() == 1 { return self.write(w, ordered[0], context) }
+                if ordered.is_empty() { return w.write_str("<mn>1</mn>") }
+                w.write_str("<mrow>")?;
+                let mut previous: Option<&Expression<I>> = None;
+                for factor in ordered {
+                    let mut rendered: String = String::new();
+                    self.write(&mut rendered, factor, Context::Factor {
+                        first: previous.is_none()
+                    })?;
+                    if let Some (previous) = previous {
+                        // juxtaposition would run digits together or read as a mixed number
+                        if Self::starts_with_number(&rendered) || (is_number(previous)
+                            && matches!(factor, Quotient (_))
+                            && self.fraction_style == FractionStyle::Stacked)
+                        {
+                            w.write_str("<mo>&#x22C5;</mo>")?;
+                        } else { w.write_str("<mo>&#x2062;</mo>")? }
+                    }
+                    w.write_str(&rendered)?;
+                    previous = Some (factor);
+                }
+                w.write_str("</mrow>")
+            }
+            Quotient (operands) => match self.fraction_style {
+                FractionStyle::Stacked => {
+                    w.write_str("<mfrac>")?;
+                    self.write(w, &operands.0, Context::Whole)?;
+                    self.write(w, &operands.1, Context::Whole)?;
+                    w.write_str("</mfrac>")
+                }
+                FractionStyle::Inline => {
+                    w.write_str("<mrow>")?;
+                    self.write(w, &operands.0, Context::Factor { first: true })?;
+                    w.write_str("<mo>/</mo>")?;
+                    self.write(w, &operands.1, Context::Divisor)?;
+                    w.write_str("</mrow>")
+                }
+            }
+            Power (operands) => match root_index(&operands.1) {
+                Some (index) if index == BigInt::from(2) => {
+                    w.write_str("<msqrt>")?;
+                    self.write(w, &operands.0, Context::Whole)?;
+                    w.write_str("</msqrt>")
+                }
+                Some (index) => {
+                    w.write_str("<mroot>")?;
+                    self.write(w, &operands.0, Context::Whole)?;
+                    write!(w, "<mn>{}</mn></mroot>", index)
+                }
+                None => {
+                    w.write_str("<msup>")?;
+                    self.write(w, &operands.0, Context::Base)?;
+                    self.write(w, &operands.1, Context::Whole)?;
+                    w.write_str("</msup>")
+                }
+            }
+            Exponential (operand) => {
+                w.write_str("<msup><mi mathvariant=\"normal\">e</mi>")?;
+                self.write(w, operand, Context::Whole)?;
+                w.write_str("</msup>")
+            }
+            Logarithm (operand) => {
+                // the invisible function application operator joins the name to its argument
+                w.write_str("<mrow><mi>ln</mi><mo>&#x2061;</mo><mrow><mo>(</mo>")?;
+                self.write(w, operand, Context::Whole)?;
+                w.write_str("<mo>)</mo></mrow></mrow>")
+            }
+            Abs (operand) => {
+                w.write_str("<mrow><mo>|</mo>")?;
+                self.write(w, operand, Context::Whole)?;
+                w.write_str("<mo>|</mo></mrow>")
+            }
+            Sign (operand) => {
+                w.write_str("<mrow><mi>sgn</mi><mo>&#x2061;</mo><mrow><mo>(</mo>")?;
+                self.write(w, operand, Context::Whole)?;
+                w.write_str("<mo>)</mo></mrow></mrow>")
+            }
+            // each branch is a row of a table after an opening brace
+            Piecewise (branches) => {
+                w.write_str("<mrow><mo>{</mo><mtable>")?;
+                for (condition, branch) in branches {
+                    w.write_str("<mtr><mtd>")?;
+                    self.write(w, branch, Context::Whole)?;
+                    w.write_str("</mtd><mtd><mrow>")?;
+                    self.write(w, condition.expression(), Context::Whole)?;
+                    w.write_str(match condition.comparison() {
+                        Comparison::Positive => "<mo>&gt;</mo>",
+                        Comparison::NonNegative => "<mo>&#x2265;</mo>",
+                        Comparison::NonZero => "<mo>&#x2260;</mo>",
+                    })?;
+                    w.write_str("<mn>0</mn></mrow></mtd></mtr>")?;
+                }
+                w.write_str("</mtable></mrow>")
+            }
+            Function (name, arguments) => {
+                w.write_str("<mrow><mi>")?;
+                escape(w, name)?;
+                w.write_str("</mi>")?;
+                self.arguments(w, arguments)
+            }
+            Derivative (name, indices, arguments) if arguments.len() == 1 => {
+                w.write_str("<mrow><msup><mi>")?;
+                escape(w, name)?;
+                match indices.len() {
+                    order @ 1..=3 => write!(w, "</mi><mo>{}</mo></msup>", "′".repeat(order))?,
+                    order => write!(
+                        w,
+                        "</mi><mrow><mo>(</mo><mn>{}</mn><mo>)</mo></mrow></msup>",
+                        order,
+                    )?,
+                }
+                self.arguments(w, arguments)
+            }
+            // partial derivatives are written with the 1-based indices of their arguments
+            Derivative (name, indices, arguments) => {
+                w.write_str("<mrow>")?;
+                for index in indices {
+                    write!(w, "<msub><mo>∂</mo><mn>{}</mn></msub>", index + 1)?;
+                }
+                w.write_str("<mi>")?;
+                escape(w, name)?;
+                w.write_str("</mi>")?;
+                self.arguments(w, arguments)
+            }
+            Variable (name) => {
+                w.write_str("<mi>")?;
+                escape(w, name)?;
+                w.write_str("</mi>")
+            }
+            Integer (integer) => write!(w, "<mn>{}</mn>", integer),
+        }
+    }
+
+    /// Writes the parenthesized arguments of an application, and closes the `mrow` opened
+    /// before the name of the function
+    fn arguments<W: Write>(&self, w: &mut W, arguments: &[Expression<I>]) -> FormatResult {
+        // the invisible function application operator joins the name to its arguments
+        w.write_str("<mo>&#x2061;</mo><mrow><mo>(</mo>")?;
+        for (index, argument) in arguments.iter().enumerate() {
+            if index > 0 { w.write_str("<mo>,</mo>")? }
+            self.write(w, argument, Context::Whole)?;
+        }
+        w.write_str("<mo>)</mo></mrow></mrow>")
+    }
+
+    /// Returns `true` if rendered MathML begins with a number, ignoring the elements that
+    /// enclose it
+    fn starts_with_number(rendered: &str) -> bool {
+        let mut rest: &str = rendered;
+        while let Some (inner) = rest.strip_prefix("<mrow>")
+            .or_else(|| rest.strip_prefix("<msup>"))
+        {
+            rest = inner;
+        }
+        rest.starts_with("<mn>")
+    }
+
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Display for MathMl<'_, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "<math xmlns=\"{}\"", NAMESPACE)?;
+        if self.block { f.write_str(" display=\"block\"")? }
+        f.write_char('>')?;
+        self.write(f, self.expression, Context::Whole)?;
+        f.write_str("</math>")
+    }
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> ContentMathMl<'_, I> {
+
+    /// Writes an `Expression` as a single Content MathML element
+    fn write<W: Write>(w: &mut W, expression: &Expression<I>) -> FormatResult {
+        use Expression::*;
+        match expression {
+            Sum (operands) | Product (operands) if operands.len() == 1 =>
+                Self::write(w, &operands[0]),
+            Sum (operands) if operands.is_empty() => w.write_str("<cn type=\"integer\">0</cn>"),
+            Product (operands) if operands.is_empty() =>
+                w.write_str("<cn type=\"integer\">1</cn>"),
+            Sum (operands) => Self::apply(w, "plus", operands),
+            Product (operands) => Self::apply(w, "times", operands),
+            Quotient (operands) => Self::apply(w, "divide", [&operands.0, &operands.1]),
+            Power (operands) => Self::apply(w, "power", [&operands.0, &operands.1]),
+            Exponential (operand) => Self::apply(w, "exp", [operand.as_ref()]),
+            Logarithm (operand) => Self::apply(w, "ln", [operand.as_ref()]),
+            Abs (operand) => Self::apply(w, "abs", [operand.as_ref()]),
+            Sign (operand) => {
+                w.write_str("<apply><ci type=\"function\">sgn</ci>")?;
+                Self::write(w, operand)?;
+                w.write_str("</apply>")
+            }
+            Piecewise (branches) => {
+                w.write_str("<piecewise>")?;
+                for (condition, branch) in branches {
+                    w.write_str("<piece>")?;
+                    Self::write(w, branch)?;
+                    let relation: &str = match condition.comparison() {
+                        Comparison::Positive => "gt",
+                        Comparison::NonNegative => "geq",
+                        Comparison::NonZero => "neq",
+                    };
+                    write!(w, "<apply><{}/>", relation)?;
+                    Self::write(w, condition.expression())?;
+                    w.write_str("<cn type=\"integer\">0</cn></apply></piece>")?;
+                }
+                w.write_str("</piecewise>")
+            }
+            Function (name, arguments) => {
+                w.write_str("<apply><ci type=\"function\">")?;
+                escape(w, name)?;
+                w.write_str("</ci>")?;
+                for argument in arguments { Self::write(w, argument)? }
+                w.write_str("</apply>")
+            }
+            // derivatives of functions of one argument are nested `diff` applications
+            Derivative (name, indices, arguments) if arguments.len() == 1 => {
+                w.write_str("<apply>")?;
+                for _ in indices { w.write_str("<apply><diff/>")? }
+                w.write_str("<ci type=\"function\">")?;
+                escape(w, name)?;
+                w.write_str("</ci>")?;
+                for _ in indices { w.write_str("</apply>")? }
+                Self::write(w, &arguments[0])?;
+                w.write_str("</apply>")
+            }
+            // partial derivatives list the 1-based indices of their arguments
+            Derivative (name, indices, arguments) => {
+                w.write_str("<apply><apply><partialdiff/><list>")?;
+                for index in indices { write!(w, "<cn type=\"integer\">{}</cn>", index + 1)? }
+                w.write_str("</list><ci type=\"function\">")?;
+                escape(w, name)?;
+                w.write_str("</ci></apply>")?;
+                for argument in arguments { Self::write(w, argument)? }
+                w.write_str("</apply>")
+            }
+            Variable (name) => {
+                w.write_str("<ci>")?;
+                escape(w, name)?;
+                w.write_str("</ci>")
+            }
+            Integer (integer) => write!(w, "<cn type=\"integer\">{}</cn>", integer),
+        }
+    }
+
+    /// Writes the application of an operator to operands
+    fn apply<'b, W: Write>(
+        w: &mut W,
+        operator: &str,
+        operands: impl IntoIterator<Item = &'b Expression<I>>,
+    ) -> FormatResult where I: 'b {
+        write!(w, "<apply><{}/>", operator)?;
+        for operand in operands { Self::write(w, operand)? }
+        w.write_str("</apply>")
+    }
+
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Display for ContentMathMl<'_, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "<math xmlns=\"{}\">", NAMESPACE)?;
+        Self::write(f, self.expression)?;
+        f.write_str("</math>")
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Returns a Presentation MathML rendering of an `Expression`, inline with stacked
+    /// fractions unless configured otherwise
+    pub fn mathml(&self) -> MathMl<'_, I> {
+        MathMl { expression: self, fraction_style: FractionStyle::default(), block: false }
+    }
+
+    /// Returns a Content MathML rendering of an `Expression`
+    pub fn content_mathml(&self) -> ContentMathMl<'_, I> { ContentMathMl { expression: self } }
+
+}
+
+/// Writes the text of a variable name with the characters that are special in XML escaped
+fn escape<W: Write, T: Display>(w: &mut W, text: &T) -> FormatResult {
+    for character in text.to_string().chars() {
+        match character {
+            '&' => w.write_str("&amp;")?,
+            '<' => w.write_str("&lt;")?,
+            '>' => w.write_str("&gt;")?,
+            '"' => w.write_str("&quot;")?,
+            character => w.write_char(character)?,
+        }
+    }
+    Ok (())
+}
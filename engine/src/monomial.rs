@@ -21,10 +21,13 @@ use std::{
 };
 
 /// A monomial
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Monomial<I: Clone + Eq + Hash + PartialEq> {
     /// A scalar
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::rational"))]
     multiplier: BigRational,
     /// Variables with exponents
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::integer_map"))]
     variables: HashMap<I, BigInt>,
     /// Other factors
     other_factors: Vec<Expression<I>>,
@@ -32,17 +35,17 @@ pub struct Monomial<I: Clone + Eq + Hash + PartialEq> {
 
 impl<I: Clone + Eq + Hash + PartialEq> Monomial<I> {
 
-    /// Creates a new `Monomial` from factors
+    /// Creates a new `Monomial` from factors that are already reduced
     pub fn from_factors(factors: &[Expression<I>]) -> Self {
         let mut multiplier: BigRational = BigRational::from(BigInt::from(1));
         let mut variables: HashMap<I, BigInt> = HashMap::new();
         let mut other_factors: Vec<Expression<I>> = vec![];
         let mut monomials: Vec<Self> = Vec::new();
         for factor in factors {
-            match factor.clone().reduce() {
+            match factor.clone() {
                 Product (factors) => monomials.push(Self::from_factors(&factors)),
                 Quotient (operands) => match (operands.0, operands.1) {
-                    (Integer (numerator), Integer (denominator)) =>
+                    (Integer (numerator), Integer (denominator)) if !denominator.is_zero() =>
                         multiplier *= BigRational::new(numerator.clone(), denominator.clone()),
                     operands => other_factors.push(Quotient (Box::new(operands))),
                 }
@@ -79,10 +82,12 @@ impl<I: Clone + Eq + Hash + PartialEq> Monomial<I> {
 
     /// Returns a `Monomial` as an expression
     pub fn to_expression(self) -> Expression<I> {
-        let factors: Vec<Expression<I>> = self.to_factors();
-        if factors.len() == 1 {
-            factors[0].clone()
-        } else { Product (factors) }
+        let mut factors: Vec<Expression<I>> = self.to_factors();
+        match factors.len() {
+            0 => Integer (BigInt::from(1)),
+            1 => factors.remove(0),
+            _ => Product (factors),
+        }
     }
 
     /// Returns a `Monomial` as its factors
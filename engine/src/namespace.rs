@@ -1,19 +1,24 @@
 // Copyright Rob Gage 2025
 
-use crate::Expression;
-use std::{
-    collections::HashMap,
-    fmt::{
-        Result as FormatResult,
-        Write,
-    }
+use crate::{
+    Assumption,
+    Assumptions,
+    Definition,
+    Definitions,
+    Expression,
 };
+use std::collections::HashMap;
 
-/// Associates variables and numeric identifiers
+/// Associates variables and functions with numeric identifiers
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Namespace {
     /// The variables in the namespace
     variables: Vec<String>,
     identifiers: HashMap<String, usize>,
+    /// The assumptions made about the variables in the namespace
+    assumptions: Assumptions,
+    /// The definitions of the functions in the namespace
+    definitions: Definitions,
 }
 
 impl Namespace {
@@ -22,98 +27,98 @@ impl Namespace {
         Self {
             variables: Vec::new(),
             identifiers: HashMap::new(),
+            assumptions: Assumptions::new(),
+            definitions: Definitions::new(),
         }
     }
 
+    /// Assumes a property of a variable, storing its name if not already stored
+    pub fn assume(&mut self, name: &str, assumption: Assumption) {
+        let identifier: usize = self.identifier(name.to_string());
+        self.assumptions.assume(identifier, assumption);
+    }
+
+    /// Returns the assumptions made about the variables in the namespace
+    pub fn assumptions(&self) -> &Assumptions { &self.assumptions }
+
+    /// Defines a function with parameters and a body, such as `f(x) = x^2 + 1`, storing the
+    /// names of the function, its parameters and the variables of its body if not already stored
+    pub fn define(&mut self, name: &str, parameters: &[&str], body: Expression<String>) {
+        let name: usize = self.identifier(name.to_string());
+        let parameters: Vec<usize> = parameters.iter()
+            .map(|parameter| self.identifier(parameter.to_string()))
+            .collect();
+        let body: Expression = self.intern(body);
+        self.definitions.define(name, Definition::new(parameters, body));
+    }
+
+    /// Returns the definitions of the functions in the namespace
+    pub fn definitions(&self) -> &Definitions { &self.definitions }
+
+    /// Returns the identifier of a variable, storing its name if not already stored
+    fn identifier(&mut self, name: String) -> usize {
+        if let Some (identifier) = self.identifiers.get(&name) { return *identifier }
+        let identifier: usize = self.variables.len();
+        self.identifiers.insert(name.clone(), identifier);
+        self.variables.push(name);
+        identifier
+    }
+
     /// Converts an `Expression` with `String` identifiers into one with `usize` identifiers,
     /// and stores the `String` identifiers
     pub fn intern(&mut self, expression: Expression<String>) -> Expression {
-        use Expression::*;
-        match expression {
-            Sum(terms) => Sum(terms.into_iter()
-                .map(|term| self.intern(term))
-                .collect()
-            ),
-            Product(factors) => Product(factors.into_iter()
-                .map(|factor| self.intern(factor))
-                .collect()
-            ),
-            Quotient(operands) => Quotient(Box::new((
-                self.intern(operands.0),
-                self.intern(operands.1)
-            ))),
-            Power(operands) => Power(Box::new((
-                self.intern(operands.0),
-                self.intern(operands.1)
-            ))),
-            Exponential(operand) => Exponential(Box::new(self.intern(*operand))),
-            Logarithm(operand) => Exponential(Box::new(self.intern(*operand))),
-            Variable(name) => if let Some(identifier) = self.identifiers.get(&name) {
-                Variable(*identifier)
-            } else {
-                let identifier: usize = self.variables.len();
-                self.identifiers.insert(name.clone(), identifier);
-                self.variables.push(name.clone());
-                Variable(identifier)
-            }
-            Integer(integer) => Integer(integer)
-        }
+        expression.map_variables(|name| self.identifier(name))
     }
 
-    /// Displays an expression as a `String` containing LaTeX math
-    fn display(&self, expression: &Expression) -> String {
-        let mut string: String = String::new();
-        self.write(&mut string, expression).unwrap();
-        string
+    /// Returns the name of a variable or function with an identifier, if stored
+    pub fn name(&self, identifier: usize) -> Option<&str> {
+        self.variables.get(identifier).map(String::as_str)
     }
 
-    /// Writes an expression as LaTeX math
-    fn write<W: Write>(&self, w: &mut W, expression: &Expression) -> FormatResult {
-        use Expression::*;
-        match expression {
-            Sum(terms) => {
-                for index in 0..terms.len() {
-                    if index != 0 { w.write_str(" + ")?; }
-                    self.write(w, &terms[index])?;
+    /// Converts an `Expression` with `usize` identifiers back into one with the stored `String`
+    /// identifiers, or returns `None` if it contains an identifier that is not stored
+    ///
+    /// (This is the reverse of `Namespace::intern`)
+    pub fn externalize(&self, expression: &Expression) -> Option<Expression<String>> {
+        let mut stored: bool = true;
+        let externalized: Expression<String> = expression.clone()
+            .map_variables(|identifier| match self.name(identifier) {
+                Some (name) => name.to_string(),
+                None => {
+                    stored = false;
+                    String::new()
                 }
-                Ok(())
-            }
-            Product(terms) => {
-                for index in 0..terms.len() {
-                    w.write_char('(')?;
-                    self.write(w, &terms[index])?;
-                    w.write_char(')')?;
-                }
-                Ok(())
-            }
-            Quotient(operands) => {
-                self.write(w, &operands.0)?;
-                w.write_str(" / ")?;
-                self.write(w, &operands.0)?;
-                Ok (())
-            },
-            Power (operands) => {
-                self.write(w, &operands.0)?;
-                w.write_str(" ^ ")?;
-                self.write(w, &operands.0)?;
-                Ok (())
-            },
-            Exponential (operand) => {
-                w.write_str("e ^ ")?;
-                self.write(w, &operand)?;
-                Ok (())
-            },
-            Logarithm (operand) => {
-                w.write_str("ln(")?;
-                self.write(w, &operand)?;
-                w.write_char(')')?;
-                Ok(())
-            },
-            Variable (identifier) => if let Some (name) = self.variables.get(*identifier) {
-                w.write_str(name)
-            } else { w.write_str("<unknown>") },
-            Integer (integer) => w.write_str(&integer.to_string()),
-        }
+            });
+        stored.then_some(externalized)
     }
 
-}
\ No newline at end of file
+    /// Renders an `Expression` as LaTeX math with the stored variable names
+    pub fn latex(&self, expression: &Expression) -> Option<String> {
+        Some (self.externalize(expression)?.latex().to_string())
+    }
+
+    /// Renders an `Expression` as inline Presentation MathML with the stored variable names
+    pub fn mathml(&self, expression: &Expression) -> Option<String> {
+        Some (self.externalize(expression)?.mathml().to_string())
+    }
+
+    /// Renders an `Expression` as Content MathML with the stored variable names
+    pub fn content_mathml(&self, expression: &Expression) -> Option<String> {
+        Some (self.externalize(expression)?.content_mathml().to_string())
+    }
+
+    /// Renders an `Expression` as two-dimensional Unicode text with the stored variable names
+    pub fn pretty(&self, expression: &Expression) -> Option<String> {
+        Some (self.externalize(expression)?.pretty().to_string())
+    }
+
+    /// Renders an `Expression` as plain text with the stored variable names
+    pub fn plain_text(&self, expression: &Expression) -> Option<String> {
+        Some (self.externalize(expression)?.plain_text().to_string())
+    }
+
+}
+
+impl Default for Namespace {
+    fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,110 @@
+// Copyright Rob Gage 2025
+
+//! Rules shared by the renderings of `Expression`s in mathematical notation, such as where
+//! parentheses and minus signs are written
+
+use crate::Expression;
+use num::{
+    bigint::BigInt,
+    One,
+    Signed,
+};
+use std::hash::Hash;
+
+/// The context of a subexpression, which determines whether it must be enclosed in
+/// parentheses
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Context {
+    /// A whole expression, such as the argument of a function or an exponent
+    Whole,
+    /// A term of a sum after the first
+    Term,
+    /// A factor of a product, which may be negated if it is the first
+    Factor { first: bool },
+    /// The divisor of an inline quotient
+    Divisor,
+    /// The base of a power
+    Base,
+}
+
+/// Returns `true` if an `Expression` must be enclosed in parentheses in a `Context`, where
+/// `inline` is `true` if quotients are written on one line with a slash
+///
+/// (Expressions written with a leading minus sign are only left bare as whole expressions and
+/// first factors)
+pub(crate) fn needs_parentheses<I: Clone + Eq + Hash + PartialEq>(
+    expression: &Expression<I>,
+    context: Context,
+    inline: bool,
+) -> bool {
+    use Expression::*;
+    if negation(expression).is_some() {
+        return !matches!(context, Context::Whole | Context::Factor { first: true })
+    }
+    match (expression, context) {
+        (Sum (terms), context) if terms.len() > 1 => context != Context::Whole,
+        (Product (factors), Context::Divisor | Context::Base) => factors.len() > 1,
+        (Quotient (_), Context::Base) => true,
+        (Quotient (_), Context::Factor { .. } | Context::Divisor) => inline,
+        (Power (_) | Exponential (_) | Logarithm (_) | Piecewise (_), Context::Base) => true,
+        _ => false,
+    }
+}
+
+/// Returns the positive counterpart of an `Expression` that is written with a leading
+/// minus sign, such as a negative integer or a product with a negative integer factor
+pub(crate) fn negation<I: Clone + Eq + Hash + PartialEq>(
+    expression: &Expression<I>,
+) -> Option<Expression<I>> {
+    use Expression::*;
+    match expression {
+        Integer (integer) if integer.is_negative() => Some (Integer (-integer)),
+        Product (factors) => {
+            let (index, positive): (usize, Expression<I>) = factors.iter()
+                .enumerate()
+                .filter(|(_, factor)| is_number(factor))
+                .find_map(|(index, factor)| Some ((index, negation(factor)?)))?;
+            let mut factors: Vec<Expression<I>> = factors.clone();
+            match positive {
+                Integer (integer) if integer.is_one() => { factors.remove(index); }
+                positive => factors[index] = positive,
+            }
+            Some (match factors.len() {
+                0 => Integer (BigInt::one()),
+                1 => factors.remove(0),
+                _ => Product (factors),
+            })
+        }
+        Quotient (operands) => negation(&operands.0)
+            .map(|dividend| Quotient (Box::new((dividend, operands.1.clone())))),
+        _ => None,
+    }
+}
+
+/// Returns `true` if an `Expression` is an integer or a quotient of integers
+pub(crate) fn is_number<I: Clone + Eq + Hash + PartialEq>(expression: &Expression<I>) -> bool {
+    match expression {
+        Expression::Integer (_) => true,
+        Expression::Quotient (operands) => matches!(
+            (&operands.0, &operands.1),
+            (Expression::Integer (_), Expression::Integer (_))
+        ),
+        _ => false,
+    }
+}
+
+/// Returns the index of the root that a power with an exponent is written as, if the
+/// exponent is the reciprocal of an integer greater than one
+pub(crate) fn root_index<I: Clone + Eq + Hash + PartialEq>(
+    exponent: &Expression<I>,
+) -> Option<BigInt> {
+    match exponent {
+        Expression::Quotient (operands) => match (&operands.0, &operands.1) {
+            (Expression::Integer (numerator), Expression::Integer (denominator))
+                if numerator.is_one() && denominator > &BigInt::one() =>
+                Some (denominator.clone()),
+            _ => None,
+        }
+        _ => None,
+    }
+}
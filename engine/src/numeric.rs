@@ -0,0 +1,108 @@
+// Copyright Rob Gage 2025
+
+use crate::Comparison;
+use num::{
+    bigint::BigInt,
+    complex::Complex,
+    traits::ToPrimitive,
+};
+use std::f64::consts::E;
+
+/// A type of number that `Expression`s can be evaluated with
+pub trait Numeric: Clone {
+
+    /// Converts an integer into a number of the same kind as a sample number
+    ///
+    /// (The sample carries any context the conversion needs, such as a precision)
+    fn from_bigint(integer: &BigInt, sample: &Self) -> Self;
+
+    /// Adds two numbers
+    fn add(&self, other: &Self) -> Self;
+
+    /// Multiplies two numbers
+    fn mul(&self, other: &Self) -> Self;
+
+    /// Divides a number by another
+    fn div(&self, other: &Self) -> Self;
+
+    /// Raises a number to the power of another
+    fn powf(&self, exponent: &Self) -> Self;
+
+    /// Applies the exponential function to a number
+    fn exp(&self) -> Self;
+
+    /// Applies the natural logarithm function to a number
+    fn ln(&self) -> Self;
+
+    /// Returns the absolute value of a number
+    fn abs(&self) -> Self;
+
+    /// Returns the sign of a number, which is `-1`, `0` or `1`
+    fn signum(&self) -> Self;
+
+    /// Returns the value of the first of a list of branches whose `Comparison` holds for the
+    /// number it compares, or an undefined number like a sample if none holds
+    ///
+    /// (Each branch is a `Comparison`, the number it compares with zero and the value of the
+    /// branch)
+    fn select(branches: &[(Comparison, Self, Self)], sample: &Self) -> Self;
+
+}
+
+impl Numeric for f64 {
+    fn from_bigint(integer: &BigInt, _: &Self) -> Self { integer.to_f64().unwrap_or(f64::NAN) }
+    fn add(&self, other: &Self) -> Self { self + other }
+    fn mul(&self, other: &Self) -> Self { self * other }
+    fn div(&self, other: &Self) -> Self { self / other }
+    fn powf(&self, exponent: &Self) -> Self {
+        // unlike `f64::powf`, which returns one for `1 ^ NaN` and `NaN ^ 0`
+        if self.is_nan() || exponent.is_nan() { f64::NAN } else { f64::powf(*self, *exponent) }
+    }
+    fn exp(&self) -> Self { E.powf(*self) }
+    fn ln(&self) -> Self { f64::ln(*self) }
+    fn abs(&self) -> Self { f64::abs(*self) }
+    fn signum(&self) -> Self { if *self == 0.0 { 0.0 } else { f64::signum(*self) } }
+    fn select(branches: &[(Comparison, Self, Self)], _: &Self) -> Self {
+        for (comparison, compared, value) in branches {
+            // undefined conditions make the selection undefined
+            if compared.is_nan() { return f64::NAN }
+            if comparison.holds(*compared) { return *value }
+        }
+        f64::NAN
+    }
+}
+
+/// Complex numbers are evaluated on the principal branch of each function
+///
+/// (Conditions of piecewise expressions only hold for real numbers)
+impl Numeric for Complex<f64> {
+    fn from_bigint(integer: &BigInt, _: &Self) -> Self {
+        Complex::from(integer.to_f64().unwrap_or(f64::NAN))
+    }
+    fn add(&self, other: &Self) -> Self { self + other }
+    fn mul(&self, other: &Self) -> Self { self * other }
+    fn div(&self, other: &Self) -> Self { self / other }
+    fn powf(&self, exponent: &Self) -> Self {
+        // integer powers are exact and defined at zero
+        if exponent.im == 0.0
+            && exponent.re.fract() == 0.0
+            && exponent.re.abs() <= i32::MAX as f64 {
+            self.powi(exponent.re as i32)
+        } else if self.re == 0.0 && self.im == 0.0 && exponent.re > 0.0 {
+            Complex::from(0.0)
+        } else { self.powc(*exponent) }
+    }
+    fn exp(&self) -> Self { Complex::exp(*self) }
+    fn ln(&self) -> Self { Complex::ln(*self) }
+    fn abs(&self) -> Self { Complex::from(self.norm()) }
+    fn signum(&self) -> Self {
+        if self.re == 0.0 && self.im == 0.0 { *self } else { self / self.norm() }
+    }
+    fn select(branches: &[(Comparison, Self, Self)], _: &Self) -> Self {
+        for (comparison, compared, value) in branches {
+            if compared.im != 0.0 || compared.re.is_nan() { return Complex::from(f64::NAN) }
+            if comparison.holds(compared.re) { return *value }
+        }
+        Complex::from(f64::NAN)
+    }
+}
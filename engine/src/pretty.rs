@@ -0,0 +1,323 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    notation::{
+        is_number,
+        needs_parentheses,
+        negation,
+        root_index,
+        Context,
+    },
+    Comparison,
+    Expression,
+};
+use num::bigint::BigInt;
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as FormatResult,
+    },
+    hash::Hash,
+};
+
+/// A two-dimensional Unicode rendering of an `Expression` for terminals, with stacked
+/// fractions, raised exponents and parentheses sized to their contents
+pub struct Pretty<'a, I: Clone + Eq + Hash + PartialEq> {
+    /// The rendered `Expression`
+    expression: &'a Expression<I>,
+}
+
+/// A rectangle of text with a baseline, which is the row that aligns with the rows of the
+/// blocks beside it
+///
+/// (Every line of a block has the same number of characters)
+struct Block {
+    /// The lines of text, from top to bottom
+    lines: Vec<String>,
+    /// The index of the line that is aligned with adjacent blocks
+    baseline: usize,
+}
+
+impl Block {
+
+    /// Creates a `Block` of a single line of text
+    fn text(text: &str) -> Self { Self { lines: vec![text.to_string()], baseline: 0 } }
+
+    /// Returns the number of characters in each line of the `Block`
+    fn width(&self) -> usize { self.lines.first().map_or(0, |line| line.chars().count()) }
+
+    /// Returns the number of lines in the `Block`
+    fn height(&self) -> usize { self.lines.len() }
+
+    /// Returns the number of lines below the baseline of the `Block`
+    fn depth(&self) -> usize { self.height() - self.baseline - 1 }
+
+    /// Returns the `Block` with another placed to its right, aligning their baselines
+    fn beside(self, other: Block) -> Self {
+        let baseline: usize = self.baseline.max(other.baseline);
+        let depth: usize = self.depth().max(other.depth());
+        let left: Vec<String> = self.extend(baseline, depth);
+        let right: Vec<String> = other.extend(baseline, depth);
+        Self {
+            lines: left.into_iter()
+                .zip(right)
+                .map(|(left, right)| left + &right)
+                .collect(),
+            baseline,
+        }
+    }
+
+    /// Returns the lines of the `Block` with blank lines added so that it has a number of
+    /// lines above and below its baseline
+    fn extend(self, above: usize, below: usize) -> Vec<String> {
+        let blank: String = " ".repeat(self.width());
+        let mut lines: Vec<String> = vec![blank.clone(); above - self.baseline];
+        let added: usize = below - self.depth();
+        lines.extend(self.lines);
+        lines.extend(vec![blank; added]);
+        lines
+    }
+
+    /// Returns the `Block` stacked over another with a horizontal bar between them
+    fn over(self, denominator: Block) -> Self {
+        let width: usize = self.width().max(denominator.width());
+        let baseline: usize = self.height();
+        let mut lines: Vec<String> = self.lines.iter()
+            .map(|line| center(line, width))
+            .collect();
+        lines.push("─".repeat(width));
+        lines.extend(denominator.lines.iter().map(|line| center(line, width)));
+        Self { lines, baseline }
+    }
+
+    /// Returns the `Block` with an exponent raised to its upper right
+    fn raised(self, exponent: Block) -> Self {
+        let (base_width, exponent_width): (usize, usize) = (self.width(), exponent.width());
+        let baseline: usize = exponent.height() + self.baseline;
+        let mut lines: Vec<String> = exponent.lines.into_iter()
+            .map(|line| " ".repeat(base_width) + &line)
+            .collect();
+        lines.extend(self.lines.into_iter().map(|line| line + &" ".repeat(exponent_width)));
+        Self { lines, baseline }
+    }
+
+    /// Returns the `Block` enclosed in parentheses as tall as it is
+    fn parenthesized(self) -> Self {
+        let height: usize = self.height();
+        let baseline: usize = self.baseline;
+        let lines: Vec<String> = self.lines.into_iter()
+            .enumerate()
+            .map(|(row, line)| match (height, row) {
+                (1, _) => format!("({})", line),
+                (_, 0) => format!("⎛{}⎞", line),
+                (_, row) if row + 1 == height => format!("⎝{}⎠", line),
+                _ => format!("⎜{}⎟", line),
+            })
+            .collect();
+        Self { lines, baseline }
+    }
+
+    /// Returns the `Block` enclosed in vertical bars as tall as it is
+    fn barred(self) -> Self {
+        let height: usize = self.height();
+        let baseline: usize = self.baseline;
+        let lines: Vec<String> = self.lines.into_iter()
+            .map(|line| if height == 1 { format!("|{}|", line) } else {
+                format!("│{}│", line)
+            })
+            .collect();
+        Self { lines, baseline }
+    }
+
+    /// Returns `Block`s stacked in rows after an opening brace as tall as they are, with the
+    /// baseline on the middle line
+    fn cases(rows: Vec<Block>) -> Self {
+        let width: usize = rows.iter().map(Block::width).max().unwrap_or(0);
+        let lines: Vec<String> = rows.into_iter()
+            .flat_map(|row| row.lines)
+            .map(|line| {
+                let padding: usize = width - line.chars().count();
+                line + &" ".repeat(padding)
+            })
+            .collect();
+        let height: usize = lines.len();
+        let baseline: usize = height.saturating_sub(1) / 2;
+        let lines: Vec<String> = lines.into_iter()
+            .enumerate()
+            .map(|(row, line)| match (height, row) {
+                (1, _) => format!("{{ {}", line),
+                (_, 0) => format!("⎧ {}", line),
+                (_, row) if row + 1 == height => format!("⎩ {}", line),
+                (_, row) if row == baseline => format!("⎨ {}", line),
+                _ => format!("⎪ {}", line),
+            })
+            .collect();
+        if lines.is_empty() { return Self::text("{") }
+        Self { lines, baseline }
+    }
+
+    /// Returns the `Block` under a radical sign with an overbar
+    ///
+    /// (The index is written before the radical sign on the bottom line if it is not two)
+    fn radical(self, index: &BigInt) -> Self {
+        let height: usize = self.height();
+        let width: usize = self.width();
+        let index: String = if index == &BigInt::from(2) { String::new() } else {
+            index.to_string()
+        };
+        let indent: String = " ".repeat(index.chars().count());
+        // the radical sign is a diagonal that rises one column to the right for every line
+        let mut lines: Vec<String> = vec![format!("{}{}{}", indent, " ".repeat(height + 1),
+            "_".repeat(width))];
+        for (row, line) in self.lines.into_iter().enumerate() {
+            lines.push(if row + 1 == height {
+                format!("{}╲╱{}{}", index, " ".repeat(height - 1), line)
+            } else {
+                format!("{}{}╱{}{}", indent, " ".repeat(height - row), " ".repeat(row), line)
+            });
+        }
+        Self { lines, baseline: self.baseline + 1 }
+    }
+
+}
+
+/// Returns a line centered in a number of characters
+fn center(line: &str, width: usize) -> String {
+    let padding: usize = width - line.chars().count();
+    format!("{}{}{}", " ".repeat(padding / 2), line, " ".repeat(padding - padding / 2))
+}
+
+/// Returns a number written with Unicode subscript digits
+fn subscript(number: usize) -> String {
+    number.to_string()
+        .chars()
+        .map(|digit| char::from_u32(0x2080 + digit as u32 - '0' as u32).unwrap_or(digit))
+        .collect()
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Pretty<'_, I> {
+
+    /// Lays out an `Expression` in a `Context`
+    fn layout(expression: &Expression<I>, context: Context) -> Block {
+        use Expression::*;
+        // quotients are always stacked
+        let parenthesized: bool = needs_parentheses(expression, context, false);
+        if let Some (positive) = negation(expression) {
+            let negated: Block = Block::text("-")
+                .beside(Self::layout(&positive, Context::Factor { first: false }));
+            return if parenthesized { negated.parenthesized() } else { negated }
+        }
+        if parenthesized { return Self::layout(expression, Context::Whole).parenthesized() }
+        match expression {
+            Sum (terms) => match terms.split_first() {
+                Some ((first, rest)) => rest.iter()
+                    .fold(Self::layout(first, context), |block, term| match negation(term) {
+                        Some (positive) => block.beside(Block::text(" - "))
+                            .beside(Self::layout(&positive, Context::Term)),
+                        None => {
+                            // sums are associative, unless they begin with a minus sign
+                            let context: Context = match term {
+                                Sum (terms) if terms.first()
+                                    .is_some_and(|term| negation(term).is_none()) =>
+                                    Context::Whole,
+                                _ => Context::Term,
+                            };
+                            block.beside(Block::text(" + ")).beside(Self::layout(term, context))
+                        }
+                    }),
+                None => Block::text("0"),
+            }
+            Product (factors) => {
+                // numbers are written before the other factors
+                let mut ordered: Vec<&Expression<I>> = factors.iter()
+                    .filter(|factor| is_number(factor))
+                    .collect();
+                ordered.extend(factors.iter().filter(|factor| !is_number(factor)));
+                match ordered.split_first() {
+                    Some ((first, [])) => Self::layout(first, context),
+                    Some ((first, rest)) => rest.iter().fold(
+                        Self::layout(first, Context::Factor { first: true }),
+                        |block, factor| block.beside(Block::text("⋅"))
+                            .beside(Self::layout(factor, Context::Factor { first: false })),
+                    ),
+                    None => Block::text("1"),
+                }
+            }
+            Quotient (operands) => Self::layout(&operands.0, Context::Whole)
+                .over(Self::layout(&operands.1, Context::Whole)),
+            Power (operands) => match root_index(&operands.1) {
+                Some (index) => Self::layout(&operands.0, Context::Whole).radical(&index),
+                None => Self::layout(&operands.0, Context::Base)
+                    .raised(Self::layout(&operands.1, Context::Whole)),
+            }
+            Exponential (operand) => Block::text("e")
+                .raised(Self::layout(operand, Context::Whole)),
+            Logarithm (operand) => Block::text("ln")
+                .beside(Self::layout(operand, Context::Whole).parenthesized()),
+            Abs (operand) => Self::layout(operand, Context::Whole).barred(),
+            Sign (operand) => Block::text("sgn")
+                .beside(Self::layout(operand, Context::Whole).parenthesized()),
+            Piecewise (branches) => Block::cases(branches.iter()
+                .map(|(condition, branch)| {
+                    let comparison: &str = match condition.comparison() {
+                        Comparison::Positive => " > 0",
+                        Comparison::NonNegative => " ≥ 0",
+                        Comparison::NonZero => " ≠ 0",
+                    };
+                    Self::layout(branch, Context::Whole)
+                        .beside(Block::text(" if "))
+                        .beside(Self::layout(condition.expression(), Context::Whole))
+                        .beside(Block::text(comparison))
+                })
+                .collect()
+            ),
+            Function (name, arguments) => Block::text(&name.to_string())
+                .beside(Self::arguments(arguments)),
+            Derivative (name, indices, arguments) if arguments.len() == 1 => {
+                let name: Block = match indices.len() {
+                    order @ 1..=3 => Block::text(&format!("{}{}", name, "′".repeat(order))),
+                    order => Block::text(&name.to_string())
+                        .raised(Block::text(&format!("({})", order))),
+                };
+                name.beside(Self::arguments(arguments))
+            }
+            // partial derivatives are written with the 1-based indices of their arguments
+            Derivative (name, indices, arguments) => {
+                let partials: String = indices.iter()
+                    .map(|index| format!("∂{}", subscript(index + 1)))
+                    .collect();
+                Block::text(&format!("{}{}", partials, name)).beside(Self::arguments(arguments))
+            }
+            Variable (name) => Block::text(&name.to_string()),
+            Integer (integer) => Block::text(&integer.to_string()),
+        }
+    }
+
+    /// Lays out the parenthesized arguments of an application
+    fn arguments(arguments: &[Expression<I>]) -> Block {
+        let mut list: Block = Block::text("");
+        for (index, argument) in arguments.iter().enumerate() {
+            if index > 0 { list = list.beside(Block::text(", ")) }
+            list = list.beside(Self::layout(argument, Context::Whole));
+        }
+        list.parenthesized()
+    }
+
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Display for Pretty<'_, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let block: Block = Self::layout(self.expression, Context::Whole);
+        let lines: Vec<&str> = block.lines.iter().map(|line| line.trim_end()).collect();
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Returns a two-dimensional Unicode rendering of an `Expression` for terminals, which
+    /// spans several lines if it contains quotients, powers or roots
+    pub fn pretty(&self) -> Pretty<'_, I> { Pretty { expression: self } }
+
+}
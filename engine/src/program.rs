@@ -0,0 +1,279 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Comparison,
+    Dag,
+    EvaluationError,
+    Expression,
+    Node,
+};
+use num::traits::ToPrimitive;
+use std::{
+    collections::HashMap,
+    f64::consts::E,
+    hash::Hash,
+};
+
+/// An instruction in a `Program`, which writes its result to the register with the same index
+/// as the instruction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Instruction {
+
+    /// Loads the input value
+    Input,
+
+    /// Loads a constant
+    Constant (f64),
+
+    /// Adds two registers
+    Add (usize, usize),
+
+    /// Multiplies two registers
+    Multiply (usize, usize),
+
+    /// Divides a register by another
+    Divide (usize, usize),
+
+    /// Raises a register to the power of another
+    Power (usize, usize),
+
+    /// Raises a register to an integer power
+    PowerInteger (usize, i32),
+
+    /// Applies the exponential function to a register
+    Exponential (usize),
+
+    /// Applies the natural logarithm function to a register
+    Logarithm (usize),
+
+    /// Takes the absolute value of a register
+    Abs (usize),
+
+    /// Takes the sign of a register
+    Sign (usize),
+
+    /// Loads the second register if the first satisfies a `Comparison`, or the third otherwise
+    ///
+    /// (The result is undefined if the first register is undefined)
+    Select (Comparison, usize, usize, usize),
+
+}
+
+impl Instruction {
+
+    /// Returns the registers read by an `Instruction`
+    fn operands(self) -> Vec<usize> {
+        use Instruction::*;
+        match self {
+            Input | Constant (_) => vec![],
+            Add (a, b) | Multiply (a, b) | Divide (a, b) | Power (a, b) => vec![a, b],
+            PowerInteger (a, _) | Exponential (a) | Logarithm (a) | Abs (a) | Sign (a) =>
+                vec![a],
+            Select (_, a, b, c) => vec![a, b, c],
+        }
+    }
+
+    /// Executes an `Instruction` with a function returning the values of registers
+    fn execute(self, register: impl Fn(usize) -> f64, input: f64) -> f64 {
+        use Instruction::*;
+        match self {
+            Input => input,
+            Constant (constant) => constant,
+            Add (a, b) => register(a) + register(b),
+            Multiply (a, b) => register(a) * register(b),
+            Divide (a, b) => register(a) / register(b),
+            Power (a, b) => register(a).powf(register(b)),
+            PowerInteger (a, exponent) => register(a).powi(exponent),
+            Exponential (a) => E.powf(register(a)),
+            Logarithm (a) => register(a).ln(),
+            Abs (a) => register(a).abs(),
+            Sign (a) => if register(a) == 0.0 { 0.0 } else { register(a).signum() },
+            Select (_, a, _, _) if register(a).is_nan() => f64::NAN,
+            Select (comparison, a, b, c) =>
+                if comparison.holds(register(a)) { register(b) } else { register(c) },
+        }
+    }
+
+}
+
+/// A compiled `Expression` of a single variable, as a flat list of register `Instruction`s
+/// with constants folded ahead of time
+#[derive(Clone, Debug)]
+pub struct Program {
+    /// The instructions of the `Program`
+    instructions: Vec<Instruction>,
+    /// The register holding the output of the `Program`
+    output: usize,
+}
+
+impl Program {
+
+    /// Returns the instructions of the `Program`
+    pub fn instructions(&self) -> &[Instruction] { &self.instructions }
+
+    /// Returns the register holding the output of the `Program`
+    pub fn output(&self) -> usize { self.output }
+
+    /// Runs the `Program` for a single input value, using a slice of at least as many
+    /// registers as there are instructions
+    pub fn run(&self, registers: &mut [f64], input: f64) -> f64 {
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            registers[index] = instruction.execute(|register| registers[register], input);
+        }
+        registers[self.output]
+    }
+
+    /// Runs the `Program` for each input value, writing the results to the outputs
+    ///
+    /// (Registers are allocated once per call, so nothing is allocated per input value)
+    pub fn evaluate(&self, inputs: &[f64], outputs: &mut [f64]) {
+        let mut registers: Vec<f64> = vec![0.0; self.instructions.len()];
+        for (input, output) in inputs.iter().zip(outputs.iter_mut()) {
+            *output = self.run(&mut registers, *input);
+        }
+    }
+
+}
+
+/// Compiles the nodes of a `Dag` into `Instruction`s
+struct Compiler<'a, I: Clone + Eq + Hash + PartialEq> {
+    /// The `Dag` being compiled
+    dag: &'a Dag<I>,
+    /// The input variable
+    variable: &'a I,
+    /// The instructions emitted so far
+    instructions: Vec<Instruction>,
+    /// The registers holding the values of compiled nodes
+    registers: HashMap<usize, usize>,
+    /// The registers holding constants
+    constants: HashMap<u64, usize>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Compiler<'_, I> {
+
+    /// Emits an `Instruction`, folding it into a constant if all of its operands are constant,
+    /// and returns its register
+    fn emit(&mut self, instruction: Instruction) -> usize {
+        let constant = |register: usize| match self.instructions[register] {
+            Instruction::Constant (constant) => Some (constant),
+            _ => None,
+        };
+        let foldable: bool = instruction != Instruction::Input && instruction.operands()
+            .into_iter()
+            .all(|operand| constant(operand).is_some());
+        if foldable {
+            let folded: f64 = instruction.execute(|operand| constant(operand).unwrap(), 0.0);
+            self.constant(folded)
+        } else {
+            self.instructions.push(instruction);
+            self.instructions.len() - 1
+        }
+    }
+
+    /// Returns the register holding a constant, emitting it if necessary
+    fn constant(&mut self, constant: f64) -> usize {
+        if let Some (register) = self.constants.get(&constant.to_bits()) { return *register }
+        self.instructions.push(Instruction::Constant (constant));
+        self.constants.insert(constant.to_bits(), self.instructions.len() - 1);
+        self.instructions.len() - 1
+    }
+
+    /// Emits a chain of binary `Instruction`s combining the values of operands
+    fn chain(
+        &mut self,
+        operands: &[usize],
+        identity: f64,
+        instruction: fn(usize, usize) -> Instruction,
+    ) -> Result<usize, EvaluationError<I>> {
+        let mut registers: Vec<usize> = Vec::with_capacity(operands.len());
+        for operand in operands { registers.push(self.compile(*operand)?) }
+        let Some ((first, rest)) = registers.split_first() else {
+            return Ok (self.constant(identity))
+        };
+        let mut register: usize = *first;
+        for other in rest { register = self.emit(instruction(register, *other)) }
+        Ok (register)
+    }
+
+    /// Compiles a node, and returns the register holding its value
+    fn compile(&mut self, identifier: usize) -> Result<usize, EvaluationError<I>> {
+        use Instruction::*;
+        if let Some (register) = self.registers.get(&identifier) { return Ok (*register) }
+        let register: usize = match self.dag.node(identifier) {
+            Node::Sum (terms) => self.chain(terms, 0.0, Add)?,
+            Node::Product (factors) => self.chain(factors, 1.0, Multiply)?,
+            Node::Quotient (dividend, divisor) => {
+                let dividend: usize = self.compile(*dividend)?;
+                let divisor: usize = self.compile(*divisor)?;
+                self.emit(Divide (dividend, divisor))
+            }
+            Node::Power (base, exponent) => {
+                let base: usize = self.compile(*base)?;
+                match self.dag.node(*exponent) {
+                    Node::Integer (integer) if integer.to_i32().is_some() =>
+                        self.emit(PowerInteger (base, integer.to_i32().unwrap())),
+                    _ => {
+                        let exponent: usize = self.compile(*exponent)?;
+                        self.emit(Power (base, exponent))
+                    }
+                }
+            }
+            Node::Exponential (operand) => {
+                let operand: usize = self.compile(*operand)?;
+                self.emit(Exponential (operand))
+            }
+            Node::Logarithm (operand) => {
+                let operand: usize = self.compile(*operand)?;
+                self.emit(Logarithm (operand))
+            }
+            Node::Abs (operand) => {
+                let operand: usize = self.compile(*operand)?;
+                self.emit(Abs (operand))
+            }
+            Node::Sign (operand) => {
+                let operand: usize = self.compile(*operand)?;
+                self.emit(Sign (operand))
+            }
+            // branches are selected from the last, which falls back to an undefined value
+            Node::Piecewise (branches) => {
+                let mut register: usize = self.constant(f64::NAN);
+                for (comparison, compared, branch) in branches.iter().rev() {
+                    let compared: usize = self.compile(*compared)?;
+                    let branch: usize = self.compile(*branch)?;
+                    register = self.emit(Select (*comparison, compared, branch, register));
+                }
+                register
+            }
+            Node::Variable (name) if name == self.variable => self.emit(Input),
+            Node::Variable (name) => return Err (EvaluationError::UnboundVariable (name.clone())),
+            Node::Function (name, _) | Node::Derivative (name, _, _) =>
+                return Err (EvaluationError::UnexpandedFunction (name.clone())),
+            Node::Integer (integer) => self.constant(integer.to_f64().unwrap_or(f64::NAN)),
+        };
+        self.registers.insert(identifier, register);
+        Ok (register)
+    }
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Compiles an `Expression` of a single variable into a `Program`
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`)
+    pub fn compile(&self, variable: &I) -> Result<Program, EvaluationError<I>> {
+        self.check_integers()?;
+        let mut dag: Dag<I> = Dag::new();
+        let root: usize = dag.insert(self);
+        let mut compiler: Compiler<I> = Compiler {
+            dag: &dag,
+            variable,
+            instructions: Vec::new(),
+            registers: HashMap::new(),
+            constants: HashMap::new(),
+        };
+        let output: usize = compiler.compile(root)?;
+        Ok (Program { instructions: compiler.instructions, output })
+    }
+
+}
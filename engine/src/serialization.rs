@@ -0,0 +1,92 @@
+// Copyright Rob Gage 2025
+
+//! Serialization of arbitrary-precision numbers as decimal strings, so that they are read back
+//! exactly by any format
+
+use num::{
+    bigint::BigInt,
+    rational::BigRational,
+};
+use serde::{
+    de::Error,
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    hash::Hash,
+    str::FromStr,
+};
+
+/// Serializes a number as a string
+fn serialize_string<T: Display, S: Serializer>(
+    number: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(number)
+}
+
+/// Deserializes a number from a string
+fn deserialize_string<'de, T: FromStr, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let string: String = String::deserialize(deserializer)?;
+    string.parse().map_err(|_| D::Error::custom(format!("invalid number `{}`", string)))
+}
+
+/// Serialization of a `BigInt` as a string
+pub(crate) mod integer {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(integer: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_string(integer, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+        deserialize_string(deserializer)
+    }
+}
+
+/// Serialization of a `BigRational` as a string, such as `-3/4`
+pub(crate) mod rational {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        rational: &BigRational,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serialize_string(rational, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BigRational, D::Error> {
+        deserialize_string(deserializer)
+    }
+}
+
+/// Serialization of a map to `BigInt`s as a map to strings
+pub(crate) mod integer_map {
+    use super::*;
+
+    pub fn serialize<K: Serialize, S: Serializer>(
+        map: &HashMap<K, BigInt>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(map.iter().map(|(key, integer)| (key, integer.to_string())))
+    }
+
+    pub fn deserialize<'de, K: Deserialize<'de> + Eq + Hash, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<K, BigInt>, D::Error> {
+        HashMap::<K, String>::deserialize(deserializer)?.into_iter()
+            .map(|(key, string)| match string.parse() {
+                Ok (integer) => Ok ((key, integer)),
+                Err (_) => Err (D::Error::custom(format!("invalid integer `{}`", string))),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Definitions,
+    Expression,
+};
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as FormatResult,
+        Write,
+    },
+    hash::Hash,
+};
+
+/// A rule of differentiation
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Rule {
+
+    /// The derivative of the variable of differentiation is one
+    Identity,
+
+    /// The derivative of any other variable is zero
+    Variable,
+
+    /// The derivative of an expression without the variable is zero
+    Constant,
+
+    /// The derivative of a sum is the sum of the derivatives of its terms
+    Sum,
+
+    /// The derivative of a product is the sum of the products of the derivative of each
+    /// factor with the other factors
+    Product,
+
+    /// The derivative of `f / g` is `(f' * g - f * g') / g^2`
+    Quotient,
+
+    /// The derivative of `a^g` for a constant `a` is `a^g * ln(a) * g'`
+    ConstantBase,
+
+    /// The derivative of `f^n` for a constant `n` is `n * f^(n - 1) * f'`
+    ConstantExponent,
+
+    /// The derivative of `f^g` is `f^g * (g' * ln(f) + g * f' / f)`
+    Power,
+
+    /// The derivative of `exp(f)` is `exp(f) * f'`
+    Exponential,
+
+    /// The derivative of `ln(f)` is `f' / f`
+    Logarithm,
+
+    /// The derivative of `f(g)` is `f'(g) * g'`, summed over the arguments of `f`
+    Chain,
+
+    /// The derivative of `|f|` is `f'` where `f > 0` and `-f'` where `f < 0`
+    Abs,
+
+    /// The derivative of `sign(f)` is zero where `f` is not zero
+    Sign,
+
+    /// The derivative of a piecewise expression is the derivative of each branch, away from
+    /// the boundaries of the conditions
+    Piecewise,
+
+}
+
+impl Rule {
+
+    /// Returns the name of the `Rule`
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::Identity => "identity rule",
+            Rule::Variable => "variable rule",
+            Rule::Constant => "constant rule",
+            Rule::Sum => "sum rule",
+            Rule::Product => "product rule",
+            Rule::Quotient => "quotient rule",
+            Rule::ConstantBase => "constant base rule",
+            Rule::ConstantExponent => "constant exponent rule",
+            Rule::Power => "general power rule",
+            Rule::Exponential => "exponential rule",
+            Rule::Logarithm => "logarithm rule",
+            Rule::Chain => "chain rule",
+            Rule::Abs => "absolute value rule",
+            Rule::Sign => "sign rule",
+            Rule::Piecewise => "piecewise rule",
+        }
+    }
+
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult { f.write_str(self.name()) }
+}
+
+/// An application of a `Rule` of differentiation to an `Expression`, with the `Step`s that
+/// differentiated its operands
+pub struct Step<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The applied rule
+    rule: Rule,
+    /// The differentiated expression
+    expression: Expression<I>,
+    /// The derivative of the expression, as produced by the rule
+    derivative: Expression<I>,
+    /// The steps that differentiated the operands of the expression, in order
+    steps: Vec<Step<I>>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Step<I> {
+
+    /// Differentiates an `Expression` with respect to a variable, recording every `Step`
+    fn new(expression: &Expression<I>, variable: &I, definitions: &Definitions<I>) -> Self {
+        let mut steps: Vec<Step<I>> = Vec::new();
+        let (rule, derivative): (Rule, Expression<I>) = expression.apply_rule(
+            variable,
+            definitions,
+            &[],
+            &mut |operand| {
+                let step: Step<I> = Step::new(operand, variable, definitions);
+                let derivative: Expression<I> = step.derivative.clone();
+                steps.push(step);
+                derivative
+            },
+        );
+        Self { rule, expression: expression.clone(), derivative, steps }
+    }
+
+    /// Returns the `Rule` applied in the `Step`
+    pub fn rule(&self) -> Rule { self.rule }
+
+    /// Returns the differentiated `Expression`
+    pub fn expression(&self) -> &Expression<I> { &self.expression }
+
+    /// Returns the derivative of the `Expression`, as produced by the `Rule` without
+    /// simplification
+    pub fn derivative(&self) -> &Expression<I> { &self.derivative }
+
+    /// Returns the `Step`s that differentiated the operands of the `Expression`
+    pub fn steps(&self) -> &[Step<I>] { &self.steps }
+
+}
+
+/// A differentiation of an `Expression` with respect to a variable, recorded as a tree of
+/// `Step`s that can be rendered for teaching
+pub struct Derivation<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The variable of differentiation
+    variable: I,
+    /// The step that differentiated the whole expression
+    step: Step<I>,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Derivation<I> {
+
+    /// Returns the variable of differentiation
+    pub fn variable(&self) -> &I { &self.variable }
+
+    /// Returns the `Step` that differentiated the whole `Expression`
+    pub fn step(&self) -> &Step<I> { &self.step }
+
+    /// Returns the derivative of the `Expression`, which is the same as the one returned by
+    /// `Expression::differentiate`
+    pub fn derivative(&self) -> &Expression<I> { &self.step.derivative }
+
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Derivation<I> {
+
+    /// Renders the `Derivation` as plain text, with one line for each `Step` indented below
+    /// the `Step` that it is part of
+    ///
+    /// (Derivatives are simplified before they are rendered)
+    pub fn plain_text(&self) -> String {
+        let mut text: String = String::new();
+        self.write_text(&mut text, &self.step, 0).expect("writing to a string cannot fail");
+        text
+    }
+
+    /// Renders the `Derivation` as a LaTeX `aligned` environment, with one row for each `Step`
+    /// indented below the `Step` that it is part of
+    ///
+    /// (Derivatives are simplified before they are rendered)
+    pub fn latex(&self) -> String {
+        let mut latex: String = String::from("\\begin{aligned}\n");
+        self.write_latex(&mut latex, &self.step, 0).expect("writing to a string cannot fail");
+        latex.push_str("\\end{aligned}");
+        latex
+    }
+
+    /// Writes a `Step` and the `Step`s that it is made of as lines of plain text
+    fn write_text<W: Write>(&self, w: &mut W, step: &Step<I>, depth: usize) -> FormatResult {
+        writeln!(
+            w,
+            "{}d/d{} [{}] = {}    ({})",
+            "    ".repeat(depth),
+            self.variable,
+            step.expression.plain_text(),
+            step.derivative.clone().reduce().plain_text(),
+            step.rule,
+        )?;
+        for substep in &step.steps { self.write_text(w, substep, depth + 1)? }
+        Ok (())
+    }
+
+    /// Writes a `Step` and the `Step`s that it is made of as rows of a LaTeX `aligned`
+    /// environment
+    fn write_latex<W: Write>(&self, w: &mut W, step: &Step<I>, depth: usize) -> FormatResult {
+        writeln!(
+            w,
+            "&{}\\frac{{d}}{{d{}}}\\left[{}\\right] = {} && \\text{{{}}} \\\\",
+            "\\quad ".repeat(depth),
+            Expression::Variable (self.variable.clone()).latex(),
+            step.expression.latex(),
+            step.derivative.clone().reduce().latex(),
+            step.rule,
+        )?;
+        for substep in &step.steps { self.write_latex(w, substep, depth + 1)? }
+        Ok (())
+    }
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Differentiates an `Expression` with respect to a variable, recording each application
+    /// of a `Rule` with the intermediate expressions
+    ///
+    /// (Derivatives of functions are kept symbolic, like `Expression::differentiate`)
+    pub fn differentiate_with_steps(&self, variable: &I) -> Derivation<I> {
+        let step: Step<I> = Step::new(self, variable, &Definitions::new());
+        Derivation { variable: variable.clone(), step }
+    }
+
+}
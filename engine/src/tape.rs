@@ -0,0 +1,133 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    dual::power_derivative,
+    Dag,
+    EvaluationError,
+    Expression,
+    Node,
+};
+use std::hash::Hash;
+
+/// A recording of an `Expression` for reverse-mode automatic differentiation, which computes
+/// the gradient with respect to any number of variables in a single backward pass
+pub struct Tape<I: Clone + Eq + Hash + PartialEq = usize> {
+    /// The shared nodes of the recorded `Expression`
+    dag: Dag<I>,
+    /// The root node of the recorded `Expression`
+    root: usize,
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Tape<I> {
+
+    /// Records an `Expression` onto a new `Tape`
+    pub fn new(expression: &Expression<I>) -> Self {
+        let mut dag: Dag<I> = Dag::new();
+        let root: usize = dag.insert(expression);
+        Self { dag, root }
+    }
+
+    /// Evaluates the recorded `Expression` and its gradient with respect to a list of
+    /// variables at a point
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`)
+    pub fn gradient(
+        &self,
+        variables: &[I],
+        point: &[f64],
+    ) -> Result<(f64, Vec<f64>), EvaluationError<I>> {
+        if point.len() != variables.len() { return Err (EvaluationError::MismatchedPoint {
+            variables: variables.len(),
+            values: point.len(),
+        })}
+        // forward pass
+        let values: Vec<f64> = self.dag.evaluate_nodes(self.root, &0.0, &|name| {
+            variables.iter().position(|other| other == name).map(|position| point[position])
+        })?;
+        let reachable: Vec<bool> = self.dag.reachable(self.root);
+        // backward pass
+        let mut adjoints: Vec<f64> = vec![0.0; self.root + 1];
+        let mut gradient: Vec<f64> = vec![0.0; variables.len()];
+        adjoints[self.root] = 1.0;
+        for index in (0..=self.root).rev().filter(|index| reachable[*index]) {
+            let adjoint: f64 = adjoints[index];
+            if adjoint == 0.0 { continue }
+            match self.dag.node(index) {
+                Node::Sum (terms) => for term in terms { adjoints[*term] += adjoint },
+                Node::Product (factors) => {
+                    // products of the other factors, without dividing by possible zeros
+                    let mut prefix: f64 = 1.0;
+                    let mut prefixes: Vec<f64> = Vec::with_capacity(factors.len());
+                    for factor in factors {
+                        prefixes.push(prefix);
+                        prefix *= values[*factor];
+                    }
+                    let mut suffix: f64 = 1.0;
+                    for (factor, prefix) in factors.iter().zip(prefixes).rev() {
+                        adjoints[*factor] += adjoint * prefix * suffix;
+                        suffix *= values[*factor];
+                    }
+                }
+                Node::Quotient (dividend, divisor) => {
+                    adjoints[*dividend] += adjoint / values[*divisor];
+                    adjoints[*divisor] -= adjoint * values[index] / values[*divisor];
+                }
+                Node::Power (base, exponent) => {
+                    let (b, e): (f64, f64) = (values[*base], values[*exponent]);
+                    adjoints[*base] += adjoint * power_derivative(b, e);
+                    // constant exponents need no adjoint, and the logarithm may be undefined
+                    if !matches!(self.dag.node(*exponent), Node::Integer (_)) {
+                        adjoints[*exponent] += adjoint * values[index] * b.ln();
+                    }
+                }
+                Node::Exponential (operand) => adjoints[*operand] += adjoint * values[index],
+                Node::Logarithm (operand) => adjoints[*operand] += adjoint / values[*operand],
+                // derivatives are undefined at kinks
+                Node::Abs (operand) => adjoints[*operand] += match values[*operand] {
+                    0.0 => f64::NAN,
+                    value => adjoint * value.signum(),
+                },
+                Node::Sign (operand) => if values[*operand] == 0.0 {
+                    adjoints[*operand] += f64::NAN
+                },
+                Node::Piecewise (branches) => {
+                    // only the selected branch receives the adjoint
+                    let mut kink: bool = false;
+                    for (comparison, compared, branch) in branches {
+                        let compared: f64 = values[*compared];
+                        if compared.is_nan() { break }
+                        kink |= compared == 0.0;
+                        if comparison.holds(compared) {
+                            adjoints[*branch] += if kink { f64::NAN } else { adjoint };
+                            break
+                        }
+                    }
+                }
+                Node::Variable (name) => for (position, other) in variables.iter().enumerate() {
+                    if other == name { gradient[position] += adjoint }
+                },
+                Node::Integer (_) => {}
+                Node::Function (..) | Node::Derivative (..) =>
+                    unreachable!("functions fail the forward pass"),
+            }
+        }
+        Ok ((values[self.root], gradient))
+    }
+
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Evaluates an `Expression` and its gradient with respect to a list of variables at a
+    /// point, using reverse-mode automatic differentiation
+    ///
+    /// (This method requires that no other unsubstituted variables remain in the `Expression`)
+    pub fn gradient(
+        &self,
+        variables: &[I],
+        point: &[f64],
+    ) -> Result<(f64, Vec<f64>), EvaluationError<I>> {
+        Tape::new(self).gradient(variables, point)
+    }
+
+}
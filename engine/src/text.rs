@@ -0,0 +1,222 @@
+// Copyright Rob Gage 2025
+
+use crate::{
+    Comparison,
+    Expression,
+};
+use num::{
+    One,
+    Signed,
+};
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as FormatResult,
+        Write,
+    },
+    hash::Hash,
+};
+
+/// A plain-text infix rendering of an `Expression`, with only the parentheses needed for it to
+/// be read back as the same `Expression`
+///
+/// (Sums and products of fewer than two operands are rendered as their values, piecewise
+/// expressions without branches and derivatives with respect to no arguments cannot be read
+/// back, names must be identifiers, and functions must not be named `exp`, `ln`, `abs`, `sign`
+/// or `piecewise`, which are read back as built-in functions)
+pub struct PlainText<'a, I: Clone + Eq + Hash + PartialEq> {
+    /// The rendered `Expression`
+    expression: &'a Expression<I>,
+}
+
+/// The position of a subexpression, which determines the operations that can be rendered
+/// inside of it without parentheses
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+enum Position {
+    /// A whole expression, such as the argument of a function
+    Expression,
+    /// A term of a sum
+    Term,
+    /// The first factor of a product
+    Multiplicand,
+    /// A later factor of a product or a divisor
+    Factor,
+    /// A base or an exponent
+    Atom,
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> PlainText<'_, I> {
+
+    /// Writes an `Expression` in a `Position`
+    fn write<W: Write>(w: &mut W, expression: &Expression<I>, position: Position) -> FormatResult {
+        use Expression::*;
+        let highest: Position = match expression {
+            Sum (terms) if terms.len() > 1 => Position::Expression,
+            Product (factors) if Self::negation(factors).is_some() => Position::Expression,
+            Product (factors) if factors.len() > 1 => Position::Term,
+            Quotient (_) => Position::Multiplicand,
+            Power (_) => Position::Factor,
+            _ => Position::Atom,
+        };
+        if position > highest {
+            w.write_char('(')?;
+            Self::write(w, expression, Position::Expression)?;
+            return w.write_char(')')
+        }
+        match expression {
+            Sum (terms) => match terms.split_first() {
+                Some ((first, rest)) if !rest.is_empty() => {
+                    // a negated first term is written with a leading minus sign
+                    let first_position: Position = match first {
+                        Product (factors) if Self::negation(factors).is_some() =>
+                            Position::Expression,
+                        _ => Position::Term,
+                    };
+                    Self::write(w, first, first_position)?;
+                    for term in rest {
+                        match term {
+                            Product (factors) if factors.len() == 2
+                                && Self::is_negative_one(&factors[0]) => {
+                                w.write_str(" - ")?;
+                                Self::write(w, &factors[1], Position::Term)?;
+                            }
+                            term => {
+                                w.write_str(" + ")?;
+                                Self::write(w, term, Position::Term)?;
+                            }
+                        }
+                    }
+                    Ok (())
+                }
+                Some ((first, _)) => Self::write(w, first, position),
+                None => w.write_char('0'),
+            }
+            Product (factors) => match (Self::negation(factors), factors.split_first()) {
+                (Some (negated), _) => {
+                    let mut operand: String = String::new();
+                    Self::write(&mut operand, negated, Position::Term)?;
+                    // `(-` directly followed by digits would be read as a negative integer
+                    if operand.starts_with(|c: char| c.is_ascii_digit()) {
+                        write!(w, "- {}", operand)
+                    } else { write!(w, "-{}", operand) }
+                }
+                (None, Some ((first, rest))) if !rest.is_empty() => {
+                    Self::write(w, first, Position::Multiplicand)?;
+                    for factor in rest {
+                        w.write_str(" * ")?;
+                        Self::write(w, factor, Position::Factor)?;
+                    }
+                    Ok (())
+                }
+                (None, Some ((first, _))) => Self::write(w, first, position),
+                (None, None) => w.write_char('1'),
+            }
+            Quotient (operands) => {
+                Self::write(w, &operands.0, Position::Term)?;
+                w.write_str(" / ")?;
+                Self::write(w, &operands.1, Position::Factor)
+            }
+            Power (operands) => {
+                Self::write(w, &operands.0, Position::Atom)?;
+                w.write_char('^')?;
+                Self::write(w, &operands.1, Position::Atom)
+            }
+            Exponential (operand) => {
+                w.write_str("exp(")?;
+                Self::write(w, operand, Position::Expression)?;
+                w.write_char(')')
+            }
+            Logarithm (operand) => {
+                w.write_str("ln(")?;
+                Self::write(w, operand, Position::Expression)?;
+                w.write_char(')')
+            }
+            Abs (operand) => {
+                w.write_str("abs(")?;
+                Self::write(w, operand, Position::Expression)?;
+                w.write_char(')')
+            }
+            Sign (operand) => {
+                w.write_str("sign(")?;
+                Self::write(w, operand, Position::Expression)?;
+                w.write_char(')')
+            }
+            // branches are written as `value if condition`, in order
+            Piecewise (branches) => {
+                w.write_str("piecewise(")?;
+                for (index, (condition, branch)) in branches.iter().enumerate() {
+                    if index > 0 { w.write_str(", ")? }
+                    Self::write(w, branch, Position::Expression)?;
+                    w.write_str(" if ")?;
+                    Self::write(w, condition.expression(), Position::Expression)?;
+                    w.write_str(match condition.comparison() {
+                        Comparison::Positive => " > 0",
+                        Comparison::NonNegative => " >= 0",
+                        Comparison::NonZero => " != 0",
+                    })?;
+                }
+                w.write_char(')')
+            }
+            Function (name, arguments) => {
+                write!(w, "{}(", name)?;
+                Self::arguments(w, arguments)
+            }
+            // derivatives of functions of one argument are written with primes, such as `f''(x)`
+            Derivative (name, indices, arguments) if arguments.len() == 1 => {
+                write!(w, "{}{}(", name, "'".repeat(indices.len()))?;
+                Self::arguments(w, arguments)
+            }
+            // partial derivatives are written with the 1-based indices of their arguments
+            Derivative (name, indices, arguments) => {
+                for index in indices { write!(w, "∂_{}", index + 1)? }
+                write!(w, " {}(", name)?;
+                Self::arguments(w, arguments)
+            }
+            Variable (name) => write!(w, "{}", name),
+            // negative integers are parenthesized, since a minus sign before digits negates the
+            // rest of the term, such as in `-2^2`
+            Integer (integer) if integer.is_negative() => write!(w, "({})", integer),
+            Integer (integer) => write!(w, "{}", integer),
+        }
+    }
+
+    /// Writes the arguments of an application and the closing parenthesis
+    fn arguments<W: Write>(w: &mut W, arguments: &[Expression<I>]) -> FormatResult {
+        for (index, argument) in arguments.iter().enumerate() {
+            if index > 0 { w.write_str(", ")? }
+            Self::write(w, argument, Position::Expression)?;
+        }
+        w.write_char(')')
+    }
+
+    /// Returns the negated operand of a product of it and negative one, which is written with
+    /// a leading minus sign
+    fn negation(factors: &[Expression<I>]) -> Option<&Expression<I>> {
+        match factors {
+            [operand, negative_one] if Self::is_negative_one(negative_one) => Some (operand),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if an `Expression` is the integer negative one
+    fn is_negative_one(expression: &Expression<I>) -> bool {
+        matches!(expression, Expression::Integer (integer) if (-integer).is_one())
+    }
+
+}
+
+impl<I: Clone + Display + Eq + Hash + PartialEq> Display for PlainText<'_, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        Self::write(f, self.expression, Position::Expression)
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Returns a plain-text infix rendering of an `Expression`, which
+    /// `syntax::parse_expression` reads back as the same `Expression` (with the exceptions
+    /// described by `PlainText`)
+    pub fn plain_text(&self) -> PlainText<'_, I> { PlainText { expression: self } }
+
+}
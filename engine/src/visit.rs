@@ -0,0 +1,221 @@
+// Copyright Rob Gage 2025
+
+use crate::Expression;
+use std::hash::Hash;
+
+/// A traversal of an `Expression` by reference
+///
+/// (Override `visit` to act on each subexpression, and call `visit_children` from it to keep
+/// descending)
+pub trait Visitor<I: Clone + Eq + Hash + PartialEq> {
+
+    /// Visits an `Expression`, visiting its children by default
+    fn visit(&mut self, expression: &Expression<I>) { self.visit_children(expression) }
+
+    /// Visits the children of an `Expression` in order
+    fn visit_children(&mut self, expression: &Expression<I>) {
+        for child in expression.children() { self.visit(child) }
+    }
+
+}
+
+/// A traversal of an `Expression` by mutable reference, which may rewrite it in place
+///
+/// (Override `visit_mut` to act on each subexpression, and call `visit_children_mut` from it to
+/// keep descending)
+pub trait VisitorMut<I: Clone + Eq + Hash + PartialEq> {
+
+    /// Visits an `Expression`, visiting its children by default
+    fn visit_mut(&mut self, expression: &mut Expression<I>) {
+        self.visit_children_mut(expression)
+    }
+
+    /// Visits the children of an `Expression` in order
+    fn visit_children_mut(&mut self, expression: &mut Expression<I>) {
+        for child in expression.children_mut() { self.visit_mut(child) }
+    }
+
+}
+
+/// A transformation of an `Expression` with identifiers of type `I` into one with identifiers of
+/// type `J`, which consumes it
+///
+/// (Override `fold` to rewrite each subexpression, and call `fold_children` from it to keep
+/// descending)
+pub trait Folder<I: Clone + Eq + Hash + PartialEq, J: Clone + Eq + Hash + PartialEq = I> {
+
+    /// Folds a variable
+    fn fold_variable(&mut self, variable: I) -> Expression<J>;
+
+    /// Folds the application of a function to arguments that are already folded
+    fn fold_function(&mut self, name: I, arguments: Vec<Expression<J>>) -> Expression<J>;
+
+    /// Folds the application of a partial derivative of a function to arguments that are
+    /// already folded
+    fn fold_derivative(
+        &mut self,
+        name: I,
+        indices: Vec<usize>,
+        arguments: Vec<Expression<J>>,
+    ) -> Expression<J>;
+
+    /// Folds an `Expression`, folding its children by default
+    fn fold(&mut self, expression: Expression<I>) -> Expression<J> {
+        self.fold_children(expression)
+    }
+
+    /// Rebuilds an `Expression` from its folded children, with its variables folded with
+    /// `fold_variable` and its applications folded with `fold_function` and `fold_derivative`
+    fn fold_children(&mut self, expression: Expression<I>) -> Expression<J> {
+        use Expression::*;
+        match expression {
+            Sum (terms) => Sum (terms.into_iter().map(|term| self.fold(term)).collect()),
+            Product (factors) => Product (factors.into_iter()
+                .map(|factor| self.fold(factor))
+                .collect()
+            ),
+            Quotient (operands) => {
+                let (dividend, divisor): (Expression<I>, Expression<I>) = *operands;
+                Quotient (Box::new((self.fold(dividend), self.fold(divisor))))
+            }
+            Power (operands) => {
+                let (base, exponent): (Expression<I>, Expression<I>) = *operands;
+                Power (Box::new((self.fold(base), self.fold(exponent))))
+            }
+            Exponential (operand) => Exponential (Box::new(self.fold(*operand))),
+            Logarithm (operand) => Logarithm (Box::new(self.fold(*operand))),
+            Abs (operand) => Abs (Box::new(self.fold(*operand))),
+            Sign (operand) => Sign (Box::new(self.fold(*operand))),
+            Piecewise (branches) => Piecewise (branches.into_iter()
+                .map(|(condition, branch)| {
+                    (condition.map(|expression| self.fold(expression)), self.fold(branch))
+                })
+                .collect()
+            ),
+            Function (name, arguments) => {
+                let arguments: Vec<Expression<J>> = arguments.into_iter()
+                    .map(|argument| self.fold(argument))
+                    .collect();
+                self.fold_function(name, arguments)
+            }
+            Derivative (name, indices, arguments) => {
+                let arguments: Vec<Expression<J>> = arguments.into_iter()
+                    .map(|argument| self.fold(argument))
+                    .collect();
+                self.fold_derivative(name, indices, arguments)
+            }
+            Variable (variable) => self.fold_variable(variable),
+            Integer (integer) => Integer (integer),
+        }
+    }
+
+}
+
+/// A `Folder` that renames every variable and function with a function
+struct Renaming<F> (F);
+
+impl<I, J, F> Folder<I, J> for Renaming<F>
+where
+    I: Clone + Eq + Hash + PartialEq,
+    J: Clone + Eq + Hash + PartialEq,
+    F: FnMut(I) -> J,
+{
+    fn fold_variable(&mut self, variable: I) -> Expression<J> {
+        Expression::Variable ((self.0)(variable))
+    }
+
+    fn fold_function(&mut self, name: I, arguments: Vec<Expression<J>>) -> Expression<J> {
+        Expression::Function ((self.0)(name), arguments)
+    }
+
+    fn fold_derivative(
+        &mut self,
+        name: I,
+        indices: Vec<usize>,
+        arguments: Vec<Expression<J>>,
+    ) -> Expression<J> {
+        Expression::Derivative ((self.0)(name), indices, arguments)
+    }
+}
+
+impl<I: Clone + Eq + Hash + PartialEq> Expression<I> {
+
+    /// Returns the direct children of an `Expression`, in order
+    pub fn children(&self) -> Vec<&Expression<I>> {
+        use Expression::*;
+        match self {
+            Sum (operands) | Product (operands) | Function (_, operands)
+            | Derivative (_, _, operands) =>
+                operands.iter().collect(),
+            Quotient (operands) | Power (operands) => vec![&operands.0, &operands.1],
+            Exponential (operand) | Logarithm (operand) | Abs (operand) | Sign (operand) =>
+                vec![operand],
+            // the expression of each condition comes before its branch
+            Piecewise (branches) => branches.iter()
+                .flat_map(|(condition, branch)| [condition.expression(), branch])
+                .collect(),
+            Variable (_) | Integer (_) => vec![],
+        }
+    }
+
+    /// Returns mutable references to the direct children of an `Expression`, in order
+    pub fn children_mut(&mut self) -> Vec<&mut Expression<I>> {
+        use Expression::*;
+        match self {
+            Sum (operands) | Product (operands) | Function (_, operands)
+            | Derivative (_, _, operands) =>
+                operands.iter_mut().collect(),
+            Quotient (operands) | Power (operands) => {
+                let (first, second): &mut (Expression<I>, Expression<I>) = operands;
+                vec![first, second]
+            }
+            Exponential (operand) | Logarithm (operand) | Abs (operand) | Sign (operand) =>
+                vec![operand],
+            Piecewise (branches) => branches.iter_mut()
+                .flat_map(|(condition, branch)| [condition.expression_mut(), branch])
+                .collect(),
+            Variable (_) | Integer (_) => vec![],
+        }
+    }
+
+    /// Returns an `Expression` with each of its direct children replaced by the result of a
+    /// function, keeping the operation that combines them
+    pub fn map_children(self, mut function: impl FnMut(Self) -> Self) -> Self {
+        use Expression::*;
+        match self {
+            Sum (terms) => Sum (terms.into_iter().map(function).collect()),
+            Product (factors) => Product (factors.into_iter().map(function).collect()),
+            Quotient (operands) => {
+                let (dividend, divisor): (Self, Self) = *operands;
+                Quotient (Box::new((function(dividend), function(divisor))))
+            }
+            Power (operands) => {
+                let (base, exponent): (Self, Self) = *operands;
+                Power (Box::new((function(base), function(exponent))))
+            }
+            Exponential (operand) => Exponential (Box::new(function(*operand))),
+            Logarithm (operand) => Logarithm (Box::new(function(*operand))),
+            Abs (operand) => Abs (Box::new(function(*operand))),
+            Sign (operand) => Sign (Box::new(function(*operand))),
+            Piecewise (branches) => Piecewise (branches.into_iter()
+                .map(|(condition, branch)| (condition.map(&mut function), function(branch)))
+                .collect()
+            ),
+            Function (name, arguments) =>
+                Function (name, arguments.into_iter().map(function).collect()),
+            Derivative (name, indices, arguments) =>
+                Derivative (name, indices, arguments.into_iter().map(function).collect()),
+            leaf @ (Variable (_) | Integer (_)) => leaf,
+        }
+    }
+
+    /// Returns an `Expression` with every variable and function renamed by a function, which may
+    /// change the type of its identifiers
+    pub fn map_variables<J: Clone + Eq + Hash + PartialEq>(
+        self,
+        function: impl FnMut(I) -> J,
+    ) -> Expression<J> {
+        Renaming (function).fold(self)
+    }
+
+}
@@ -0,0 +1,60 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Assumption,
+    Assumptions,
+    Dag,
+    Expression,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn y() -> E { E::Variable ("y".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+/// Reduces an `Expression` both as a tree and in a `Dag`, checking that the results agree
+fn reduce(expression: &E, assumptions: &Assumptions<String>) -> E {
+    let reduced: E = expression.clone().reduce_with(assumptions);
+    let mut dag: Dag<String> = Dag::with_assumptions(assumptions.clone());
+    let identifier: usize = dag.insert(expression);
+    let identifier: usize = dag.reduce(identifier);
+    assert!(dag.expression(identifier) == reduced, "{}", expression.plain_text());
+    reduced
+}
+
+#[test]
+fn reductions_of_nested_products_take_linear_time() {
+    // ((x + 1) * x + 1) * x ..., which takes exponential time if factors are reduced twice
+    let mut expression: E = x();
+    for _ in 0..40 { expression = E::Product (vec![E::Sum (vec![expression, integer(1)]), x()]) }
+    let assumptions: Assumptions<String> = Assumptions::new()
+        .with("x".to_string(), Assumption::Positive);
+    let reduced: E = expression.reduce_with(&assumptions);
+    assert!(reduced.clone().reduce_with(&assumptions) == reduced);
+}
+
+#[test]
+fn cancellations_require_operands_to_be_defined() {
+    let nonzero: Assumptions<String> = Assumptions::new()
+        .with("x".to_string(), Assumption::NonZero);
+    // (x / y) / (x / y) is undefined where y = 0
+    let ratio: E = E::Quotient (Box::new((x(), y())));
+    let quotient: E = E::Quotient (Box::new((ratio.clone(), ratio)));
+    assert!(reduce(&quotient, &nonzero) != integer(1));
+    let both: Assumptions<String> = nonzero.clone().with("y".to_string(), Assumption::NonZero);
+    assert!(reduce(&quotient, &both) == integer(1));
+    // (x ^ (1 / 2)) ^ 0 is undefined where x < 0
+    let root: E = E::Power (Box::new((x(), E::Quotient (Box::new((integer(1), integer(2)))))));
+    let power: E = E::Power (Box::new((root, integer(0))));
+    assert!(reduce(&power, &nonzero) != integer(1));
+    let positive: Assumptions<String> = Assumptions::new()
+        .with("x".to_string(), Assumption::Positive);
+    assert!(reduce(&power, &positive) == integer(1));
+    // x ^ -2 is defined wherever x is nonzero
+    let reciprocal: E = E::Power (Box::new((x(), integer(-2))));
+    assert!(reduce(&E::Power (Box::new((reciprocal, integer(0)))), &nonzero) == integer(1));
+}
@@ -0,0 +1,59 @@
+// Copyright Rob Gage 2025
+
+use engine::Expression;
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+/// Returns the forward-mode and reverse-mode derivatives of an `Expression` at a point
+fn derivatives(expression: &E, point: f64) -> [f64; 2] {
+    let variable: String = "x".to_string();
+    let forward: f64 = expression.evaluate_derivative(&variable, &[point]).unwrap()[0];
+    let (_, reverse): (f64, Vec<f64>) = expression.gradient(&[variable], &[point]).unwrap();
+    [forward, reverse[0]]
+}
+
+#[test]
+fn zero_exponents_have_zero_derivatives_at_zero_bases() {
+    // x ^ (0 * x), where the exponent is zero but not constant (which reverse mode leaves
+    // undefined, since the adjoint of the exponent is multiplied by ln(0))
+    let power: E = E::Power (Box::new((x(), E::Product (vec![integer(0), x()]))));
+    assert_eq!(derivatives(&power, 0.0)[0], 0.0);
+    let constant: E = E::Power (Box::new((x(), integer(0))));
+    assert_eq!(derivatives(&constant, 0.0), [0.0, 0.0]);
+}
+
+#[test]
+fn constants_at_kinks_have_zero_derivatives() {
+    // x + sign(0) + |0|
+    let expression: E = E::Sum (vec![
+        x(),
+        E::Sign (Box::new(integer(0))),
+        E::Abs (Box::new(integer(0))),
+    ]);
+    assert_eq!(derivatives(&expression, 1.5), [1.0, 1.0]);
+    // sign(exp(x)) is constant even where exp(x) is too large to differentiate
+    let sign: E = E::Sign (Box::new(E::Exponential (Box::new(E::Exponential (Box::new(x()))))));
+    assert_eq!(derivatives(&sign, 8.0), [0.0, 0.0]);
+}
+
+#[test]
+fn powers_of_undefined_values_are_undefined() {
+    // |(-1) ^ x| ^ 0 and 1 ^ ln(-x)
+    let zeroth: E = E::Power (Box::new((
+        E::Abs (Box::new(E::Power (Box::new((integer(-1), x()))))),
+        integer(0),
+    )));
+    let one: E = E::Power (Box::new((
+        integer(1),
+        E::Logarithm (Box::new(E::Product (vec![integer(-1), x()]))),
+    )));
+    for expression in [zeroth, one] {
+        assert!(expression.evaluate(&"x".to_string(), &[0.5]).unwrap()[0].is_nan());
+        assert!(derivatives(&expression, 0.5).iter().all(|derivative| derivative.is_nan()));
+    }
+}
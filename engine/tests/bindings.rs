@@ -0,0 +1,41 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Bindings,
+    Expression,
+    Symbol,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+#[test]
+fn repeated_subexpressions_are_bound_once() {
+    // (x + 1) ^ 2 + ln(x + 1) * (x + 1) ^ 2
+    let shifted: E = E::Sum (vec![x(), integer(1)]);
+    let square: E = E::Power (Box::new((shifted.clone(), integer(2))));
+    let expression: E = E::Sum (vec![
+        square.clone(),
+        E::Product (vec![E::Logarithm (Box::new(shifted)), square]),
+    ]);
+    let bindings: Bindings<String> = expression.eliminate_common_subexpressions();
+    let binding = |index: usize| Expression::Variable (Symbol::Binding (index));
+    assert_eq!(bindings.bindings().len(), 2);
+    assert!(bindings.bindings()[0] == Expression::Sum (vec![
+        Expression::Variable (Symbol::Variable ("x".to_string())),
+        Expression::Integer (BigInt::from(1)),
+    ]));
+    assert!(bindings.bindings()[1] == Expression::Power (Box::new((
+        binding(0),
+        Expression::Integer (BigInt::from(2)),
+    ))));
+    let variable: String = "x".to_string();
+    let inputs: [f64; 3] = [0.0, 1.5, 4.0];
+    let bound: Vec<f64> = bindings.evaluate_with(&variable, &inputs).unwrap();
+    let expected: Vec<f64> = expression.evaluate(&variable, &inputs).unwrap();
+    assert_eq!(bound, expected);
+}
@@ -0,0 +1,58 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    Language,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn y() -> E { E::Variable ("y".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+fn power(base: E, exponent: E) -> E { E::Power (Box::new((base, exponent))) }
+
+#[test]
+fn small_powers_in_glsl_are_products_of_locals() {
+    // (x + y) ^ 3 + (x * y) ^ -2
+    let expression: E = E::Sum (vec![
+        power(E::Sum (vec![x(), y()]), integer(3)),
+        power(E::Product (vec![x(), y()]), integer(-2)),
+    ]);
+    let source: String = expression.codegen(Language::Glsl, "f").to_string();
+    assert!(source.contains("float t0 = x + y;"), "{}", source);
+    assert!(source.contains("float t1 = x * y;"), "{}", source);
+    assert!(source.contains("t0 * t0 * t0 + 1.0 / (t1 * t1)"), "{}", source);
+    assert!(!source.contains("pow("), "{}", source);
+}
+
+#[test]
+fn negative_powers_are_reciprocals() {
+    let expression: E = power(x(), integer(-3));
+    for (language, expected) in [
+        (Language::Rust, "1.0 / f64::powi(x, 3)"),
+        (Language::C, "1.0 / pow(x, 3.0)"),
+        (Language::Python, "1.0 / x ** 3"),
+        (Language::Glsl, "1.0 / (x * x * x)"),
+    ] {
+        let source: String = expression.codegen(language, "f").to_string();
+        assert!(source.contains(expected), "{}", source);
+    }
+}
+
+#[test]
+fn signs_in_rust_and_c_compute_their_operands_once() {
+    let expression: E = E::Sign (Box::new(E::Sum (vec![x(), integer(-1)])));
+    for (language, local) in [
+        (Language::Rust, "let t0: f64 = x - 1.0;"),
+        (Language::C, "const double t0 = x - 1.0;"),
+    ] {
+        let source: String = expression.codegen(language, "f").to_string();
+        assert!(source.contains(local), "{}", source);
+        assert_eq!(source.matches("x - 1.0").count(), 1, "{}", source);
+    }
+}
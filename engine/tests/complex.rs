@@ -0,0 +1,52 @@
+// Copyright Rob Gage 2025
+
+use engine::Expression;
+use num::{
+    bigint::BigInt,
+    complex::Complex,
+};
+use std::f64::consts::PI;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+/// Returns `true` if two complex numbers are equal up to rounding
+fn close(a: Complex<f64>, b: Complex<f64>) -> bool { (a - b).norm() <= 1e-12 }
+
+#[test]
+fn complex_evaluation_uses_principal_branches() {
+    let variable: String = "x".to_string();
+    // ln(x) at -1 is i * pi
+    let logarithm: E = E::Logarithm (Box::new(x()));
+    let value: Complex<f64> = logarithm.evaluate_complex(&variable, &[Complex::from(-1.0)])
+        .unwrap()[0];
+    assert!(close(value, Complex::new(0.0, PI)));
+    assert!(logarithm.evaluate(&variable, &[-1.0]).unwrap()[0].is_nan());
+    // x ^ (1 / 2) at -4 is 2 * i
+    let root: E = E::Power (Box::new((x(), E::Quotient (Box::new((integer(1), integer(2)))))));
+    let value: Complex<f64> = root.evaluate_complex(&variable, &[Complex::from(-4.0)])
+        .unwrap()[0];
+    assert!(close(value, Complex::new(0.0, 2.0)));
+}
+
+#[test]
+fn complex_roots_of_polynomials_evaluate_to_zero() {
+    let variable: String = "x".to_string();
+    // x ^ 2 - 2 * x + 5, with roots 1 + 2 * i and 1 - 2 * i
+    let polynomial: E = E::Sum (vec![
+        E::Power (Box::new((x(), integer(2)))),
+        E::Product (vec![integer(-2), x()]),
+        integer(5),
+    ]);
+    let roots: [Complex<f64>; 2] = [Complex::new(1.0, 2.0), Complex::new(1.0, -2.0)];
+    for value in polynomial.evaluate_complex(&variable, &roots).unwrap() {
+        assert!(close(value, Complex::from(0.0)), "{}", value);
+    }
+    // real inputs agree with real evaluation
+    let real: Complex<f64> = polynomial.evaluate_complex(&variable, &[Complex::from(3.0)])
+        .unwrap()[0];
+    assert!(close(real, Complex::from(polynomial.evaluate(&variable, &[3.0]).unwrap()[0])));
+}
@@ -0,0 +1,120 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Assumption,
+    Assumptions,
+    Dag,
+    Expression,
+    Node,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+/// Reduces an `Expression` in a `Dag` and converts the result back
+fn reduce(dag: &mut Dag<String>, expression: &E) -> E {
+    let identifier: usize = dag.insert(expression);
+    let reduced: usize = dag.reduce(identifier);
+    dag.expression(reduced)
+}
+
+#[test]
+fn reductions_agree_with_expressions_where_undefined() {
+    for expression in [
+        E::Quotient (Box::new((integer(0), integer(0)))),
+        E::Quotient (Box::new((integer(0), x()))),
+        E::Power (Box::new((x(), integer(0)))),
+        E::Power (Box::new((integer(0), integer(0)))),
+    ] {
+        let reduced: E = reduce(&mut Dag::new(), &expression);
+        assert!(reduced == expression.clone().reduce(), "{}", expression.plain_text());
+    }
+}
+
+#[test]
+fn reductions_use_assumptions() {
+    let assumptions: Assumptions<String> = Assumptions::new()
+        .with("x".to_string(), Assumption::NonZero);
+    let mut dag: Dag<String> = Dag::with_assumptions(assumptions);
+    assert!(reduce(&mut dag, &E::Quotient (Box::new((integer(0), x())))) == integer(0));
+    assert!(reduce(&mut dag, &E::Power (Box::new((x(), integer(0))))) == integer(1));
+}
+
+#[test]
+fn reductions_of_shared_nodes_take_linear_time() {
+    // a chain of quotients that would be exponentially large as a tree
+    let mut dag: Dag<String> = Dag::new();
+    let mut identifier: usize = dag.add(Node::Variable ("x".to_string()));
+    for _ in 0..40 {
+        let power: usize = dag.add(Node::Power (identifier, identifier));
+        identifier = dag.add(Node::Quotient (power, power));
+    }
+    let reduced: usize = dag.reduce(identifier);
+    assert!(dag.len() < 200 && reduced == identifier);
+}
+
+#[test]
+fn evaluations_agree_with_expressions() {
+    // ln(x) * x ^ (1 / 2) + exp(-x) / |x - 1|
+    let expression: E = E::Sum (vec![
+        E::Product (vec![
+            E::Logarithm (Box::new(x())),
+            E::Power (Box::new((x(), E::Quotient (Box::new((integer(1), integer(2))))))),
+        ]),
+        E::Quotient (Box::new((
+            E::Exponential (Box::new(E::Product (vec![integer(-1), x()]))),
+            E::Abs (Box::new(E::Sum (vec![x(), integer(-1)]))),
+        ))),
+    ]);
+    let variable: String = "x".to_string();
+    let values: [f64; 4] = [-1.0, 0.5, 1.0, 2.5];
+    let mut dag: Dag<String> = Dag::new();
+    let identifier: usize = dag.insert(&expression);
+    let expected: Vec<f64> = expression.evaluate(&variable, &values).unwrap();
+    let actual: Vec<f64> = dag.evaluate(identifier, &variable, &values).unwrap();
+    for (expected, actual) in expected.into_iter().zip(actual) {
+        assert!(expected.to_bits() == actual.to_bits() || (expected - actual).abs() < 1e-12);
+    }
+    let (value, _): (f64, Vec<f64>) = expression
+        .gradient(std::slice::from_ref(&variable), &[2.5])
+        .unwrap();
+    assert!((value - expression.evaluate(&variable, &[2.5]).unwrap()[0]).abs() < 1e-12);
+    let function: E = E::Function ("f".to_string(), vec![x()]);
+    let identifier: usize = dag.insert(&function);
+    assert!(dag.evaluate(identifier, &variable, &values).is_err());
+}
+
+#[test]
+fn powers_too_large_to_compute_are_left_unevaluated() {
+    let huge: E = E::Power (Box::new((integer(10), integer(4_000_000_000))));
+    assert!(huge.clone().reduce() == huge);
+    assert!(reduce(&mut Dag::new(), &huge) == huge);
+    let small: E = E::Power (Box::new((integer(-2), integer(5))));
+    assert!(small.clone().reduce() == integer(-32));
+    assert!(reduce(&mut Dag::new(), &small) == integer(-32));
+    // powers of units stay small for any exponent
+    let unit: E = E::Power (Box::new((integer(-1), integer(4_000_000_001))));
+    assert!(unit.reduce() == integer(-1));
+}
+
+#[test]
+fn constant_subtrees_have_zero_derivatives() {
+    // (-2) ^ 3 * x + ln(-1)
+    let expression: E = E::Sum (vec![
+        E::Product (vec![E::Power (Box::new((integer(-2), integer(3)))), x()]),
+        E::Logarithm (Box::new(integer(-1))),
+    ]);
+    let variable: String = "x".to_string();
+    let mut dag: Dag<String> = Dag::new();
+    let identifier: usize = dag.insert(&expression);
+    let derivative: usize = dag.differentiate(identifier, &variable);
+    let actual: Vec<f64> = dag.evaluate(derivative, &variable, &[1.0]).unwrap();
+    let expected: Vec<f64> = expression.differentiate(&variable)
+        .evaluate(&variable, &[1.0])
+        .unwrap();
+    assert!(actual == vec![-8.0] && expected == vec![-8.0]);
+}
@@ -0,0 +1,63 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    Interval,
+};
+use num::{
+    bigint::BigInt,
+    rational::BigRational,
+};
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+fn rational(numerator: i64, denominator: i64) -> BigRational {
+    BigRational::new(BigInt::from(numerator), BigInt::from(denominator))
+}
+
+#[test]
+fn fractional_powers_of_negative_numbers_are_undefined_in_every_backend() {
+    let cube_root: E = E::Power (Box::new((
+        x(),
+        E::Quotient (Box::new((integer(1), integer(3)))),
+    )));
+    let variable: String = "x".to_string();
+    assert!(cube_root.evaluate_decimal(&variable, &rational(-8, 1), 20).is_none());
+    assert!(cube_root.evaluate_exact(&variable, &rational(-8, 1)).is_none());
+    assert!(cube_root.evaluate(&variable, &[-8.0]).unwrap()[0].is_nan());
+    let enclosure: Interval = cube_root
+        .evaluate_interval(&variable, Interval::new(-8.0, -8.0))
+        .unwrap();
+    assert!(!enclosure.contains(-2.0));
+    let two: String = cube_root.evaluate_decimal(&variable, &rational(8, 1), 20)
+        .unwrap()
+        .to_string();
+    assert_eq!(two, "2");
+}
+
+#[test]
+fn exact_powers_too_large_to_compute_are_rejected() {
+    let power: E = E::Power (Box::new((integer(3), integer(1_000_000_000))));
+    assert!(power.evaluate_exact(&"x".to_string(), &rational(0, 1)).is_none());
+    let unit: E = E::Power (Box::new((integer(-1), integer(1_000_000_001))));
+    assert_eq!(unit.evaluate_exact(&"x".to_string(), &rational(0, 1)), Some (rational(-1, 1)));
+}
+
+#[test]
+fn decimal_powers_with_exponents_too_large_to_represent_are_rejected() {
+    let huge: BigInt = BigInt::from(1u128 << 70);
+    let variable: String = "x".to_string();
+    for exponent in [huge.clone(), -huge] {
+        let power: E = E::Power (Box::new((x(), E::Integer (exponent))));
+        assert!(power.evaluate_decimal(&variable, &rational(10, 1), 20).is_none());
+    }
+    let small: E = E::Power (Box::new((x(), integer(-300))));
+    let value: String = small.evaluate_decimal(&variable, &rational(10, 1), 20)
+        .unwrap()
+        .to_string();
+    assert!(value.contains("-300"), "{}", value);
+}
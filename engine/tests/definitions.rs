@@ -0,0 +1,45 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Definition,
+    Definitions,
+    Expression,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+#[test]
+fn recursive_definitions_keep_their_derivatives_symbolic() {
+    // f(x) = x * f(x - 1), g(x) = h(x) + 1, h(x) = g(x) ^ 2
+    let f: E = E::Function ("f".to_string(), vec![E::Sum (vec![x(), integer(-1)])]);
+    let g: E = E::Function ("g".to_string(), vec![x()]);
+    let h: E = E::Function ("h".to_string(), vec![x()]);
+    let definitions: Definitions<String> = Definitions::new()
+        .with("f".to_string(), Definition::new(vec!["x".to_string()], E::Product (vec![x(), f])))
+        .with("g".to_string(), Definition::new(vec!["x".to_string()], E::Sum (vec![
+            h.clone(),
+            integer(1),
+        ])))
+        .with("h".to_string(), Definition::new(vec!["x".to_string()], E::Power (Box::new((
+            g.clone(),
+            integer(2),
+        )))));
+    let variable: String = "x".to_string();
+    let applied: E = E::Function ("f".to_string(), vec![x()]);
+    let derivative: String = applied.differentiate_with(&variable, &definitions)
+        .plain_text()
+        .to_string();
+    assert!(derivative.contains("f'(x + (-1))"), "{}", derivative);
+    let derivative: String = g.differentiate_with(&variable, &definitions)
+        .plain_text()
+        .to_string();
+    assert!(derivative.contains("g'(x)"), "{}", derivative);
+    let partial: E = E::Derivative ("h".to_string(), vec![0], vec![x()]);
+    let expanded: String = partial.expand(&definitions).plain_text().to_string();
+    assert!(expanded.contains("h'(x)"), "{}", expanded);
+}
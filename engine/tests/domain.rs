@@ -0,0 +1,54 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Condition,
+    Domain,
+    Expression,
+    Interval,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+#[test]
+fn piecewise_expressions_require_a_selected_branch() {
+    // x if x > 0, -x if -x > 0
+    let absolute: E = E::Piecewise (vec![
+        (Condition::Positive (x()), x()),
+        (Condition::Positive (E::Product (vec![integer(-1), x()])), integer(0)),
+    ]);
+    let domain: Domain<String> = absolute.domain(&"x".to_string());
+    assert!(domain.conditions().is_empty() && domain.disjunctions().len() == 1);
+    assert!(!domain.contains(0.0).unwrap() && domain.contains(-1.0).unwrap());
+    assert_eq!(domain.violations(0.0).unwrap().len(), 2);
+    let intervals: Vec<Interval> = domain.intervals(-1.0, 1.0, 1e-3).unwrap();
+    assert!(intervals.len() == 2 && !intervals.iter().any(|interval| interval.contains(0.0)));
+    // 0 if x != 0
+    let single: E = E::Piecewise (vec![(Condition::NonZero (x()), integer(0))]);
+    let domain: Domain<String> = single.domain(&"x".to_string());
+    assert!(domain.conditions().len() == 1 && domain.disjunctions().is_empty());
+}
+
+#[test]
+fn branches_must_be_defined_where_they_are_selected() {
+    let logarithm: E = E::Logarithm (Box::new(E::Sum (vec![x(), integer(-5)])));
+    // ln(x - 5) if x > 0
+    let single: E = E::Piecewise (vec![(Condition::Positive (x()), logarithm.clone())]);
+    let domain: Domain<String> = single.domain(&"x".to_string());
+    assert!(!domain.contains(3.0).unwrap() && domain.contains(6.0).unwrap());
+    // ln(x - 5) if x > 0, 0 if x + 10 > 0
+    let guarded: E = E::Piecewise (vec![
+        (Condition::Positive (x()), logarithm),
+        (Condition::Positive (E::Sum (vec![x(), integer(10)])), integer(0)),
+    ]);
+    let domain: Domain<String> = guarded.domain(&"x".to_string());
+    assert!(!domain.contains(3.0).unwrap() && !domain.contains(-20.0).unwrap());
+    assert!(domain.contains(6.0).unwrap() && domain.contains(-1.0).unwrap());
+    let intervals: Vec<Interval> = domain.intervals(-20.0, 20.0, 1e-3).unwrap();
+    assert!(intervals.len() == 2 && intervals[0].contains(-5.0) && !intervals[0].contains(1.0));
+    assert!(intervals[1].contains(10.0) && !intervals[1].contains(4.9));
+}
@@ -0,0 +1,57 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Condition,
+    DomainError,
+    EvaluationError,
+    Expression,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+#[test]
+fn strict_evaluation_reports_the_first_sample_outside_of_the_domain() {
+    let variable: String = "x".to_string();
+    // ln(x) + 3 / x
+    let expression: E = E::Sum (vec![
+        E::Logarithm (Box::new(x())),
+        E::Quotient (Box::new((integer(3), x()))),
+    ]);
+    assert_eq!(expression.evaluate_strict(&variable, &[1.0]), Ok (vec![3.0]));
+    assert_eq!(
+        expression.evaluate_strict(&variable, &[1.0, -2.0, 0.0]),
+        Err (EvaluationError::Domain { sample: 1, error: DomainError::Logarithm (-2.0) }),
+    );
+    let reciprocal: E = E::Quotient (Box::new((integer(3), x())));
+    assert_eq!(
+        reciprocal.evaluate_strict(&variable, &[0.0]),
+        Err (EvaluationError::Domain { sample: 0, error: DomainError::DivisionByZero (3.0) }),
+    );
+    let unselected: E = E::Piecewise (vec![(Condition::Positive (x()), x())]);
+    assert_eq!(
+        unselected.evaluate_strict(&variable, &[-1.0]),
+        Err (EvaluationError::Domain { sample: 0, error: DomainError::Piecewise }),
+    );
+    // evaluation that is not strict yields `NaN`
+    assert!(expression.evaluate(&variable, &[-2.0]).unwrap()[0].is_nan());
+}
+
+#[test]
+fn evaluation_reports_unbound_variables_and_unexpanded_functions() {
+    let variable: String = "x".to_string();
+    let unbound: E = E::Sum (vec![x(), E::Variable ("y".to_string())]);
+    assert_eq!(
+        unbound.evaluate(&variable, &[1.0]),
+        Err (EvaluationError::UnboundVariable ("y".to_string())),
+    );
+    let applied: E = E::Function ("f".to_string(), vec![x()]);
+    assert_eq!(
+        applied.evaluate_strict(&variable, &[1.0]),
+        Err (EvaluationError::UnexpandedFunction ("f".to_string())),
+    );
+}
@@ -0,0 +1,31 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    Generator,
+    Visitor,
+};
+
+type E = Expression<String>;
+
+/// A `Visitor` that collects the indices of every derivative
+struct Indices (Vec<Vec<usize>>);
+
+impl Visitor<String> for Indices {
+    fn visit(&mut self, expression: &E) {
+        if let E::Derivative (_, indices, _) = expression { self.0.push(indices.clone()) }
+        self.visit_children(expression)
+    }
+}
+
+#[test]
+fn generated_derivatives_have_ascending_indices() {
+    let mut indices: Indices = Indices (Vec::new());
+    for seed in 0..20 {
+        let mut generator: Generator<String> = Generator::new(seed, vec!["x".to_string()])
+            .functions(vec![("f".to_string(), 1), ("g".to_string(), 3)]);
+        for _ in 0..50 { indices.visit(&generator.generate()) }
+    }
+    assert!(indices.0.iter().any(|indices| indices.len() > 1 && indices[0] != indices[1]));
+    assert!(indices.0.iter().all(|indices| indices.is_sorted()));
+}
@@ -0,0 +1,44 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    Interval,
+};
+use num::{
+    bigint::BigInt,
+    rational::BigRational,
+};
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+/// Returns `true` if the `Interval` of an `Expression` at a point encloses its value computed
+/// with 40 significant digits
+fn encloses(expression: &E, point: i64) -> bool {
+    let variable: String = "x".to_string();
+    let enclosure: Interval = expression.evaluate_interval(&variable, Interval::point(point as f64))
+        .unwrap();
+    let precise: f64 = expression.evaluate_decimal(
+        &variable,
+        &BigRational::from_integer(BigInt::from(point)),
+        40,
+    ).unwrap().to_f64();
+    enclosure.contains(precise)
+}
+
+#[test]
+fn enclosures_hold_at_large_arguments() {
+    let exponential: E = E::Exponential (Box::new(x()));
+    for point in [-700, -300, 300, 700, 709] {
+        assert!(encloses(&exponential, point), "exp({})", point);
+    }
+    // (1 + 1 / x) ^ 1000001 at x = 10000, close to exp(100)
+    let power: E = E::Power (Box::new((
+        E::Sum (vec![integer(1), E::Quotient (Box::new((integer(1), x())))]),
+        integer(1_000_001),
+    )));
+    assert!(encloses(&power, 10_000));
+}
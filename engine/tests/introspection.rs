@@ -0,0 +1,63 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    Operation,
+};
+use num::bigint::BigInt;
+use std::collections::HashMap;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn y() -> E { E::Variable ("y".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+fn power(base: E, exponent: E) -> E { E::Power (Box::new((base, exponent))) }
+
+#[test]
+fn sizes_and_variables() {
+    // y * x ^ 2 + ln(x)
+    let expression: E = E::Sum (vec![
+        E::Product (vec![y(), power(x(), integer(2))]),
+        E::Logarithm (Box::new(x())),
+    ]);
+    assert_eq!(expression.free_variables(), ["y", "x"]);
+    assert!(expression.contains(&"x".to_string()));
+    assert!(!expression.contains(&"z".to_string()));
+    assert_eq!(expression.node_count(), 8);
+    assert_eq!(expression.depth(), 4);
+    assert_eq!(expression.operation(), Some (Operation::Sum));
+    let counts: HashMap<Operation, usize> = HashMap::from([
+        (Operation::Sum, 1),
+        (Operation::Product, 1),
+        (Operation::Power, 1),
+        (Operation::Logarithm, 1),
+    ]);
+    assert_eq!(expression.operation_counts(), counts);
+}
+
+#[test]
+fn polynomials_and_rational_functions() {
+    let variable: String = "x".to_string();
+    // (x + 1) ^ 3 * x / y
+    let polynomial: E = E::Quotient (Box::new((
+        E::Product (vec![power(E::Sum (vec![x(), integer(1)]), integer(3)), x()]),
+        y(),
+    )));
+    assert_eq!(polynomial.degree_in(&variable), Some (BigInt::from(4)));
+    assert!(polynomial.is_polynomial_in(&variable));
+    assert!(polynomial.is_rational_in(&variable));
+    // 1 / x
+    let rational: E = E::Quotient (Box::new((integer(1), x())));
+    assert_eq!(rational.degree_in(&variable), None);
+    assert!(rational.is_rational_in(&variable));
+    // x ^ (1 / 2)
+    let radical: E = power(x(), E::Quotient (Box::new((integer(1), integer(2)))));
+    assert!(!radical.is_rational_in(&variable));
+    // functions of other variables are constants
+    let constant: E = E::Exponential (Box::new(y()));
+    assert_eq!(constant.degree_in(&variable), Some (BigInt::from(0)));
+}
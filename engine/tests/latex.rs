@@ -0,0 +1,58 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    FractionStyle,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn variable(name: &str) -> E { E::Variable (name.to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+fn quotient(dividend: E, divisor: E) -> E { E::Quotient (Box::new((dividend, divisor))) }
+
+/// Renders an `Expression` as LaTeX with stacked and with inline fractions
+fn render(expression: &E) -> (String, String) {
+    (
+        expression.latex().to_string(),
+        expression.latex().fraction_style(FractionStyle::Inline).to_string(),
+    )
+}
+
+#[test]
+fn negations_and_subtractions_are_parenthesized_by_precedence() {
+    // -(a + b)
+    let negated: E = E::Product (vec![E::Sum (vec![variable("a"), variable("b")]), integer(-1)]);
+    assert_eq!(render(&negated).0, "-\\left(\\mathit{a} + \\mathit{b}\\right)");
+    // a - b
+    let difference: E = E::Sum (vec![
+        variable("a"),
+        E::Product (vec![integer(-1), variable("b")]),
+    ]);
+    assert_eq!(render(&difference).0, "\\mathit{a} - \\mathit{b}");
+}
+
+#[test]
+fn fractions_are_stacked_or_inline() {
+    // (a / b) ^ c
+    let power: E = E::Power (Box::new((quotient(variable("a"), variable("b")), variable("c"))));
+    assert_eq!(render(&power), (
+        "\\left(\\frac{\\mathit{a}}{\\mathit{b}}\\right)^{\\mathit{c}}".to_string(),
+        "\\left(\\mathit{a} / \\mathit{b}\\right)^{\\mathit{c}}".to_string(),
+    ));
+    // (a + 1) / b
+    let fraction: E = quotient(E::Sum (vec![variable("a"), integer(1)]), variable("b"));
+    assert_eq!(render(&fraction), (
+        "\\frac{\\mathit{a} + 1}{\\mathit{b}}".to_string(),
+        "\\left(\\mathit{a} + 1\\right) / \\mathit{b}".to_string(),
+    ));
+    // a / (b * c)
+    let nested: E = quotient(variable("a"), E::Product (vec![variable("b"), variable("c")]));
+    assert_eq!(render(&nested).1, "\\mathit{a} / \\left(\\mathit{b}\\mathit{c}\\right)");
+    // 2 * (1 / x) is not read as a mixed number
+    let scaled: E = E::Product (vec![integer(2), quotient(integer(1), variable("x"))]);
+    assert_eq!(render(&scaled).0, "2 \\cdot \\frac{1}{\\mathit{x}}");
+}
@@ -0,0 +1,54 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    FractionStyle,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn variable(name: &str) -> E { E::Variable (name.to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+fn quotient(dividend: E, divisor: E) -> E { E::Quotient (Box::new((dividend, divisor))) }
+
+/// Wraps MathML in an inline `<math>` element
+fn math(content: &str) -> String {
+    format!("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>", content)
+}
+
+#[test]
+fn presentation_mathml_parenthesizes_by_precedence() {
+    // -(a + b)
+    let negated: E = E::Product (vec![E::Sum (vec![variable("a"), variable("b")]), integer(-1)]);
+    assert_eq!(negated.mathml().to_string(), math(
+        "<mrow><mo>&#x2212;</mo><mrow><mo>(</mo><mrow><mi>a</mi><mo>+</mo><mi>b</mi></mrow>\
+        <mo>)</mo></mrow></mrow>"
+    ));
+    // (a / b) ^ c
+    let power: E = E::Power (Box::new((quotient(variable("a"), variable("b")), variable("c"))));
+    assert_eq!(power.mathml().to_string(), math(
+        "<msup><mrow><mo>(</mo><mfrac><mi>a</mi><mi>b</mi></mfrac><mo>)</mo></mrow><mi>c</mi>\
+        </msup>"
+    ));
+    assert_eq!(power.mathml().fraction_style(FractionStyle::Inline).to_string(), math(
+        "<msup><mrow><mo>(</mo><mrow><mi>a</mi><mo>/</mo><mi>b</mi></mrow><mo>)</mo></mrow>\
+        <mi>c</mi></msup>"
+    ));
+    let block: String = power.mathml().block(true).to_string();
+    assert!(block.starts_with(
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\"><msup>"
+    ));
+}
+
+#[test]
+fn content_mathml_encodes_structure() {
+    // (a + 1) / b
+    let fraction: E = quotient(E::Sum (vec![variable("a"), integer(1)]), variable("b"));
+    assert_eq!(fraction.content_mathml().to_string(), math(
+        "<apply><divide/><apply><plus/><ci>a</ci><cn type=\"integer\">1</cn></apply><ci>b</ci>\
+        </apply>"
+    ));
+}
@@ -0,0 +1,36 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    Namespace,
+};
+
+type E = Expression<String>;
+
+fn variable(name: &str) -> E { E::Variable (name.to_string()) }
+
+#[test]
+fn interned_expressions_are_printed_with_their_names() {
+    // ln(x / y) + x ^ y
+    let expression: E = E::Sum (vec![
+        E::Logarithm (Box::new(E::Quotient (Box::new((variable("x"), variable("y")))))),
+        E::Power (Box::new((variable("x"), variable("y")))),
+    ]);
+    let mut namespace: Namespace = Namespace::new();
+    let interned: Expression = namespace.intern(expression.clone());
+    assert!(interned == Expression::Sum (vec![
+        Expression::Logarithm (Box::new(Expression::Quotient (Box::new((
+            Expression::Variable (0),
+            Expression::Variable (1),
+        ))))),
+        Expression::Power (Box::new((Expression::Variable (0), Expression::Variable (1)))),
+    ]));
+    assert!(namespace.externalize(&interned) == Some (expression.clone()));
+    assert_eq!(namespace.plain_text(&interned).unwrap(), "ln(x / y) + x^y");
+    assert_eq!(namespace.latex(&interned), Some (expression.latex().to_string()));
+    assert_eq!(namespace.mathml(&interned), Some (expression.mathml().to_string()));
+    assert_eq!(namespace.pretty(&interned), Some (expression.pretty().to_string()));
+    // identifiers that were never interned have no names
+    let unknown: Expression = Expression::Sum (vec![interned, Expression::Variable (2)]);
+    assert!(namespace.externalize(&unknown).is_none() && namespace.latex(&unknown).is_none());
+}
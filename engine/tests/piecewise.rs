@@ -0,0 +1,58 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Condition,
+    Dag,
+    Domain,
+    Expression,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+#[test]
+fn derivatives_are_undefined_at_kinks() {
+    let variable: String = "x".to_string();
+    for expression in [
+        E::Abs (Box::new(x())),
+        E::Sign (Box::new(x())),
+        E::Abs (Box::new(E::Sum (vec![x(), integer(0)]))),
+    ] {
+        let derivative: E = expression.differentiate(&variable);
+        let values: Vec<f64> = derivative.evaluate(&variable, &[-1.0, 0.0, 1.0]).unwrap();
+        assert!(values[0].is_finite() && values[1].is_nan() && values[2].is_finite());
+        let domain: Domain<String> = derivative.domain(&variable);
+        assert!(!domain.contains(0.0).unwrap() && domain.contains(1.0).unwrap());
+        assert!(expression.evaluate_derivative(&variable, &[0.0]).unwrap()[0].is_nan());
+    }
+}
+
+#[test]
+fn piecewise_derivatives_are_only_undefined_on_boundaries_of_selected_branches() {
+    let variable: String = "x".to_string();
+    let below = |bound: i64| Condition::Positive (E::Sum (vec![
+        integer(bound),
+        E::Product (vec![integer(-1), x()]),
+    ]));
+    // x if x - 5 < 0, 0 if x - 3 < 0, x if x - 7 < 0
+    let expression: E = E::Piecewise (vec![
+        (below(5), x()),
+        (below(3), integer(0)),
+        (below(7), x()),
+    ]);
+    let mut dag: Dag<String> = Dag::new();
+    let identifier: usize = dag.insert(&expression);
+    let derivative: usize = dag.differentiate(identifier, &variable);
+    for derivative in [expression.differentiate(&variable), dag.expression(derivative)] {
+        let values: Vec<f64> = derivative.evaluate(&variable, &[3.0, 5.0, 6.0]).unwrap();
+        assert!(values[0] == 1.0 && values[1].is_nan() && values[2] == 1.0);
+        let domain: Domain<String> = derivative.domain(&variable);
+        assert!(domain.contains(3.0).unwrap() && !domain.contains(5.0).unwrap());
+    }
+    let automatic: Vec<f64> = expression.evaluate_derivative(&variable, &[3.0, 5.0]).unwrap();
+    assert!(automatic[0] == 1.0 && automatic[1].is_nan());
+}
@@ -0,0 +1,41 @@
+// Copyright Rob Gage 2025
+
+use engine::Expression;
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn variable(name: &str) -> E { E::Variable (name.to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+fn quotient(dividend: E, divisor: E) -> E { E::Quotient (Box::new((dividend, divisor))) }
+
+#[test]
+fn fractions_are_stacked_on_the_baseline() {
+    // (a + 1) / b
+    let fraction: E = quotient(E::Sum (vec![variable("a"), integer(1)]), variable("b"));
+    assert_eq!(fraction.pretty().to_string(), "a + 1\n─────\n  b");
+    // x + 1 / (x + 1)
+    let sum: E = E::Sum (vec![
+        variable("x"),
+        quotient(integer(1), E::Sum (vec![variable("x"), integer(1)])),
+    ]);
+    assert_eq!(sum.pretty().to_string(), "      1\nx + ─────\n    x + 1");
+}
+
+#[test]
+fn parentheses_and_radicals_are_sized_to_their_contents() {
+    // -(a + b) stays on one line
+    let negated: E = E::Product (vec![E::Sum (vec![variable("a"), variable("b")]), integer(-1)]);
+    assert_eq!(negated.pretty().to_string(), "-(a + b)");
+    // (a / b) ^ c
+    let power: E = E::Power (Box::new((quotient(variable("a"), variable("b")), variable("c"))));
+    assert_eq!(power.pretty().to_string(), "   c\n⎛a⎞\n⎜─⎟\n⎝b⎠");
+    // (x + 1) ^ (1 / 2)
+    let root: E = E::Power (Box::new((
+        E::Sum (vec![variable("x"), integer(1)]),
+        quotient(integer(1), integer(2)),
+    )));
+    assert_eq!(root.pretty().to_string(), "  _____\n╲╱x + 1");
+}
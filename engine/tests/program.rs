@@ -0,0 +1,81 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Condition,
+    EvaluationError,
+    Expression,
+    Instruction,
+    Program,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+#[test]
+fn programs_agree_with_evaluation() {
+    let variable: String = "x".to_string();
+    let inputs: [f64; 5] = [-2.0, -0.5, 0.0, 0.5, 3.0];
+    for expression in [
+        // x ^ 3 - 2 * x + 1
+        E::Sum (vec![
+            E::Power (Box::new((x(), integer(3)))),
+            E::Product (vec![integer(-2), x()]),
+            integer(1),
+        ]),
+        // ln(x) / x
+        E::Quotient (Box::new((E::Logarithm (Box::new(x())), x()))),
+        // exp(|x|) * sign(x)
+        E::Product (vec![
+            E::Exponential (Box::new(E::Abs (Box::new(x())))),
+            E::Sign (Box::new(x())),
+        ]),
+        // x ^ (1 / 2) if x > 0, -x if -x >= 0
+        E::Piecewise (vec![
+            (
+                Condition::Positive (x()),
+                E::Power (Box::new((x(), E::Quotient (Box::new((integer(1), integer(2))))))),
+            ),
+            (
+                Condition::NonNegative (E::Product (vec![integer(-1), x()])),
+                E::Product (vec![integer(-1), x()]),
+            ),
+        ]),
+    ] {
+        let program: Program = expression.compile(&variable).unwrap();
+        let mut outputs: [f64; 5] = [0.0; 5];
+        program.evaluate(&inputs, &mut outputs);
+        let expected: Vec<f64> = expression.evaluate(&variable, &inputs).unwrap();
+        for (output, expected) in outputs.iter().zip(expected) {
+            assert!(
+                *output == expected
+                    || (output.is_nan() && expected.is_nan())
+                    || (output - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+                "{}: {} != {}",
+                expression.plain_text(),
+                output,
+                expected,
+            );
+        }
+    }
+}
+
+#[test]
+fn constants_are_folded_and_other_variables_are_rejected() {
+    let variable: String = "x".to_string();
+    // 2 ^ 10 * x
+    let scaled: E = E::Product (vec![E::Power (Box::new((integer(2), integer(10)))), x()]);
+    let program: Program = scaled.compile(&variable).unwrap();
+    assert!(program.instructions().contains(&Instruction::Constant (1024.0)));
+    assert!(!program.instructions().iter().any(|instruction| {
+        matches!(instruction, Instruction::Power (..) | Instruction::PowerInteger (..))
+    }));
+    let other: E = E::Sum (vec![x(), E::Variable ("y".to_string())]);
+    assert_eq!(
+        other.compile(&variable).unwrap_err(),
+        EvaluationError::UnboundVariable ("y".to_string()),
+    );
+}
@@ -0,0 +1,21 @@
+// Copyright Rob Gage 2025
+
+#![cfg(feature = "serde")]
+
+use engine::{
+    Expression,
+    Generator,
+};
+
+#[test]
+fn generated_expressions_are_read_back_from_json() {
+    for seed in 0..20 {
+        let mut generator: Generator<String> = Generator::new(seed, vec!["x".to_string()]);
+        for _ in 0..20 {
+            let expression: Expression<String> = generator.generate();
+            let json: String = serde_json::to_string(&expression).unwrap();
+            let read: Expression<String> = serde_json::from_str(&json).unwrap();
+            assert!(read == expression, "{}", json);
+        }
+    }
+}
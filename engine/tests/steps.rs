@@ -0,0 +1,56 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Derivation,
+    Expression,
+    Rule,
+    Step,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+/// Returns the rules of a `Step` and the `Step`s it is made of, in order
+fn rules(step: &Step<String>) -> Vec<Rule> {
+    let mut collected: Vec<Rule> = vec![step.rule()];
+    for substep in step.steps() { collected.extend(rules(substep)) }
+    collected
+}
+
+#[test]
+fn steps_record_each_rule_and_the_same_derivative() {
+    // x ^ 2 * ln(x)
+    let expression: E = E::Product (vec![
+        E::Power (Box::new((x(), integer(2)))),
+        E::Logarithm (Box::new(x())),
+    ]);
+    let derivation: Derivation<String> = expression.differentiate_with_steps(&"x".to_string());
+    assert!(derivation.derivative() == &expression.differentiate(&"x".to_string()));
+    assert!(derivation.step().expression() == &expression);
+    assert_eq!(
+        rules(derivation.step()),
+        vec![
+            Rule::Product,
+            Rule::ConstantExponent,
+            Rule::Identity,
+            Rule::Logarithm,
+            Rule::Identity,
+        ],
+    );
+}
+
+#[test]
+fn plain_text_indents_substeps_below_their_step() {
+    let expression: E = E::Exponential (Box::new(x()));
+    let text: String = expression.differentiate_with_steps(&"x".to_string()).plain_text();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", text);
+    assert!(lines[0].starts_with("d/dx [exp(x)] = "), "{}", text);
+    assert!(lines[0].ends_with("(exponential rule)"), "{}", text);
+    assert!(lines[1].starts_with("    d/dx [x] = 1"), "{}", text);
+    assert!(lines[1].ends_with("(identity rule)"), "{}", text);
+}
@@ -0,0 +1,118 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    Folder,
+    Visitor,
+    VisitorMut,
+};
+use num::bigint::BigInt;
+
+type E = Expression<String>;
+
+fn x() -> E { E::Variable ("x".to_string()) }
+
+fn y() -> E { E::Variable ("y".to_string()) }
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+/// A `Visitor` that records the integers of an `Expression` in the order they are visited
+struct Integers (Vec<BigInt>);
+
+impl Visitor<String> for Integers {
+    fn visit(&mut self, expression: &E) {
+        if let E::Integer (integer) = expression { self.0.push(integer.clone()) }
+        self.visit_children(expression)
+    }
+}
+
+/// A `VisitorMut` that replaces every `x` with `y`
+struct Substitution;
+
+impl VisitorMut<String> for Substitution {
+    fn visit_mut(&mut self, expression: &mut E) {
+        if *expression == x() { *expression = y() } else { self.visit_children_mut(expression) }
+    }
+}
+
+/// A `Folder` that numbers every variable and function by the order in which it is folded
+struct Numbering (Vec<String>);
+
+impl Numbering {
+    fn number(&mut self, name: String) -> usize {
+        match self.0.iter().position(|other| *other == name) {
+            Some (index) => index,
+            None => { self.0.push(name); self.0.len() - 1 }
+        }
+    }
+}
+
+impl Folder<String, usize> for Numbering {
+    fn fold_variable(&mut self, variable: String) -> Expression<usize> {
+        Expression::Variable (self.number(variable))
+    }
+
+    fn fold_function(
+        &mut self,
+        name: String,
+        arguments: Vec<Expression<usize>>,
+    ) -> Expression<usize> {
+        Expression::Function (self.number(name), arguments)
+    }
+
+    fn fold_derivative(
+        &mut self,
+        name: String,
+        indices: Vec<usize>,
+        arguments: Vec<Expression<usize>>,
+    ) -> Expression<usize> {
+        Expression::Derivative (self.number(name), indices, arguments)
+    }
+}
+
+#[test]
+fn visitors_visit_children_in_order() {
+    // 1 + 2 * x / (3 - 4)
+    let expression: E = E::Sum (vec![
+        integer(1),
+        E::Quotient (Box::new((
+            E::Product (vec![integer(2), x()]),
+            E::Sum (vec![integer(3), integer(-4)]),
+        ))),
+    ]);
+    let mut integers: Integers = Integers (Vec::new());
+    integers.visit(&expression);
+    let expected: Vec<BigInt> = [1, 2, 3, -4].into_iter().map(BigInt::from).collect();
+    assert_eq!(integers.0, expected);
+}
+
+#[test]
+fn mutable_visitors_rewrite_in_place() {
+    let mut expression: E = E::Exponential (Box::new(E::Product (vec![x(), x(), integer(2)])));
+    Substitution.visit_mut(&mut expression);
+    assert!(expression == E::Exponential (Box::new(E::Product (vec![y(), y(), integer(2)]))));
+}
+
+#[test]
+fn folders_change_the_type_of_identifiers() {
+    // f(y, x) + x
+    let expression: E = E::Sum (vec![
+        E::Function ("f".to_string(), vec![y(), x()]),
+        x(),
+    ]);
+    let mut numbering: Numbering = Numbering (Vec::new());
+    let folded: Expression<usize> = numbering.fold(expression);
+    // arguments are folded before the function they are applied to
+    assert!(folded == Expression::Sum (vec![
+        Expression::Function (2, vec![Expression::Variable (0), Expression::Variable (1)]),
+        Expression::Variable (1),
+    ]));
+    assert_eq!(numbering.0, ["y", "x", "f"]);
+}
+
+#[test]
+fn map_variables_renames_variables_and_functions() {
+    let expression: E = E::Function ("f".to_string(), vec![x()]);
+    let renamed: E = expression.map_variables(|name| name.to_uppercase());
+    assert!(renamed == E::Function ("F".to_string(), vec![E::Variable ("X".to_string())]));
+}
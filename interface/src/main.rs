@@ -1,32 +1,105 @@
 // Copyright Rob Gage 2025
 
-use engine::Expression;
-use syntax::parse_expression;
-use std::io::{
-    stdin,
-    stdout,
-    Write,
+use engine::{
+    Condition,
+    Definition,
+    Definitions,
+    Domain,
+    Expression,
+    Generator,
+    Harness,
 };
+use syntax::{
+    parse_definition,
+    parse_expression,
+};
+use std::{
+    env::args,
+    io::{
+        stdin,
+        stdout,
+        Write,
+    },
+};
+
+/// The number of generated expressions checked by `--check`
+const CHECKED: usize = 1000;
 
 fn main() {
+    // generated expressions are checked instead of differentiating input if requested
+    if args().any(|argument| argument == "--check") { return check() }
+    // expressions are laid out in two dimensions unless plain text is requested
+    let plain: bool = args().any(|argument| argument == "--plain");
+    // each application of a rule of differentiation is printed if steps are requested
+    let steps: bool = args().any(|argument| argument == "--steps");
+    let render = |expression: &Expression<String>| if plain {
+        expression.plain_text().to_string()
+    } else { expression.pretty().to_string() };
+    // functions defined with input such as `f(x) = x^2 + 1` are differentiated through
+    let mut definitions: Definitions<String> = Definitions::new();
     loop {
         print!("differentiate expression: ");
         stdout().flush().unwrap();
         let mut expression_string: String = String::new();
         stdin().read_line(&mut expression_string).unwrap();
+        if let Ok ((name, parameters, body)) = parse_definition(&expression_string) {
+            println!("\nDefined {}({})\n\n", name, parameters.join(", "));
+            definitions.define(name, Definition::new(parameters, body));
+            continue
+        }
         print!("with respect to variable: ");
         stdout().flush().unwrap();
         let mut variable_string: String = String::new();
         stdin().read_line(&mut variable_string).unwrap();
         match parse_expression(&expression_string) {
             Ok (expression) => {
-                println!("\nParsed: {}\n", expression);
+                let variable: String = variable_string.trim().to_string();
+                println!("\nParsed:\n\n{}\n", render(&expression));
+                let expanded: Expression<String> = expression.expand(&definitions);
+                let domain: Domain<String> = expanded.domain(&variable);
+                if !domain.is_unrestricted() {
+                    let describe = |condition: &Condition<String>| match condition {
+                        Condition::Positive (expression) =>
+                            format!("{} > 0", expression.plain_text()),
+                        Condition::NonNegative (expression) =>
+                            format!("{} >= 0", expression.plain_text()),
+                        Condition::NonZero (expression) =>
+                            format!("{} != 0", expression.plain_text()),
+                    };
+                    let conditions: Vec<String> = domain.conditions().iter()
+                        .map(describe)
+                        .chain(domain.disjunctions().iter().map(|disjunction| disjunction.iter()
+                            .map(describe)
+                            .collect::<Vec<String>>()
+                            .join(" or ")
+                        ))
+                        .collect();
+                    println!("Defined where: {}\n", conditions.join(", "));
+                }
+                if steps {
+                    println!(
+                        "Steps:\n\n{}",
+                        expanded.differentiate_with_steps(&variable).plain_text()
+                    );
+                }
                 println!(
-                    "Differentiated: {}\n\n",
-                    expression.differentiate(&variable_string.trim().to_string())
+                    "Differentiated:\n\n{}\n\n",
+                    render(&expression.differentiate_with(&variable, &definitions))
                 );
             }
             Err (_) => println!("\nInvalid expression\n\n"),
         };
     }
-}
\ No newline at end of file
+}
+
+/// Checks derivatives, reductions and parse round trips of generated expressions of `x`, and
+/// prints the first that fails
+fn check() {
+    let variable: String = "x".to_string();
+    let harness: Harness = Harness::new();
+    let mut generator: Generator<String> = Generator::new(0, vec![variable.clone()]);
+    match harness.run(&mut generator, &variable, CHECKED, |text| parse_expression(text).ok()) {
+        Ok (()) => println!("checked {} generated expressions", CHECKED),
+        Err ((expression, failure)) => println!("{}\n    {}", expression.plain_text(), failure),
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright Rob Gage 2025
 
 use num_bigint::BigInt;
-use engine::{Expression, Syntax};
+use engine::{Comparison, Condition, Expression, Syntax};
 use pups::*;
 use std::str::FromStr;
 
@@ -12,6 +12,27 @@ pub fn parse_expression(syntax: &str) -> Result<Syntax, ()> {
 }
 
 
+/// Attempts to parse a function definition, such as `f(x) = x^2 + 1`, from a `&str`, returning
+/// the name, parameters and body of the function
+pub fn parse_definition(syntax: &str) -> Result<(String, Vec<String>, Syntax), ()> {
+    let text: Text = Text::from_string(syntax.trim());
+    unicode_identifier()
+        .then(delimited(
+            token("(").then(whitespace().or_not()),
+            unicode_identifier().then(repeated(comma.ignore_then(unicode_identifier()))),
+            whitespace().or_not().then(token(")")),
+        ))
+        .then_ignore(delimited(whitespace().or_not(), token("="), whitespace().or_not()))
+        .then(expression)
+        .map(|((name, (first, rest)), body): ((&str, (&str, Vec<&str>)), Syntax)| {
+            let mut parameters: Vec<String> = vec![first.to_string()];
+            parameters.extend(rest.into_iter().map(str::to_string));
+            (name.to_string(), parameters, body)
+        })
+        .parse(&text)
+}
+
+
 /// Parses an `Syntax` from syntax
 fn expression(input: &Text) -> Result<Syntax, ()> {
     choice((
@@ -51,10 +72,18 @@ fn quaternary(input: &Text) -> Result<Syntax, ()> {
             tertiary,
         ), 1)).map(|(first, rest)| {
             let mut expression: Syntax = first;
+            // consecutive factors are collected into a single product
+            let mut is_product: bool = false;
             for (is_factor, other) in rest {
-                if is_factor { expression = Expression::Product (vec![expression, other]) } else {
-                    expression = Expression::Quotient (Box::new((expression, other)))
-                }
+                expression = match (is_factor, expression) {
+                    (true, Expression::Product (mut factors)) if is_product => {
+                        factors.push(other);
+                        Expression::Product (factors)
+                    }
+                    (true, expression) => Expression::Product (vec![expression, other]),
+                    (false, expression) => Expression::Quotient (Box::new((expression, other))),
+                };
+                is_product = is_factor;
             }
             expression
         }),
@@ -89,8 +118,48 @@ fn secondary(input: &Text) -> Result<Syntax, ()> {
 }
 
 
-/// Parses a primary syntax element (named functions, variables, integers, parentheses)
+/// Parses a primary syntax element (named functions, piecewise expressions, variables,
+/// integers, parentheses, bars)
+///
+/// (Negative integers are parenthesized, such as `(-2)`, since a minus sign elsewhere negates
+/// the rest of its term, such as in `-2^2`)
 fn primary(input: &Text) -> Result<Syntax, ()> {
+    choice((
+        builtin,
+        piecewise,
+        partial,
+        // `Function`, or `Derivative` with respect to the first argument if followed by primes
+        unicode_identifier().then(repeated(token("'"))).then(delimited(
+            token("(").then(whitespace().or_not()),
+            expression.then(repeated(comma.ignore_then(expression))),
+            whitespace().or_not().then(token(")")),
+        )).map(|((name, primes), (first, rest)): ((&str, Vec<_>), (Syntax, Vec<Syntax>))| {
+            let mut arguments: Vec<Syntax> = vec![first];
+            arguments.extend(rest);
+            match primes.len() {
+                0 => Syntax::Function (name.to_string(), arguments),
+                order => Syntax::Derivative (name.to_string(), vec![0; order], arguments),
+            }
+        }),
+        // negative `Integer`
+        delimited(
+            token("(").then(whitespace().or_not()).then(token("-")),
+            number(),
+            whitespace().or_not().then(token(")")),
+        ).map(|number| Syntax::Integer (-BigInt::from_str(number).unwrap())),
+        // `Integer`
+        number().map(|number| Syntax::Integer (BigInt::from_str(number).unwrap())),
+        // `Variable`
+        unicode_identifier()
+            .map(|identifier: &str| Syntax::Variable (identifier.to_string())),
+        parentheses,
+        bars,
+    )).parse(input)
+}
+
+
+/// Parses an application of a built-in function (`exp`, `ln`, `abs` or `sign`)
+fn builtin(input: &Text) -> Result<Syntax, ()> {
     choice((
         // `Exponential`
         delimited(
@@ -105,16 +174,92 @@ fn primary(input: &Text) -> Result<Syntax, ()> {
             expression,
             whitespace().or_not().then(token(")")),
         ).map(|term| Syntax::Logarithm (Box::new(term))),
-        // `Integer`
-        number().map(|number| Syntax::Integer (BigInt::from_str(number).unwrap())),
-        // `Variable`
-        unicode_identifier()
-            .map(|identifier: &str| Syntax::Variable (identifier.to_string())),
-        parentheses,
+        // `Abs`
+        delimited(
+            token("abs(").then(whitespace().or_not()),
+            expression,
+            whitespace().or_not().then(token(")")),
+        ).map(|term| Syntax::Abs (Box::new(term))),
+        // `Sign`
+        delimited(
+            token("sign(").then(whitespace().or_not()),
+            expression,
+            whitespace().or_not().then(token(")")),
+        ).map(|term| Syntax::Sign (Box::new(term))),
     )).parse(input)
 }
 
 
+/// Parses a piecewise expression, such as `piecewise(x if x > 0, -x if -x >= 0)`, whose
+/// branches are selected in order
+fn piecewise(input: &Text) -> Result<Syntax, ()> {
+    delimited(
+        token("piecewise(").then(whitespace().or_not()),
+        branch.then(repeated(comma.ignore_then(branch))),
+        whitespace().or_not().then(token(")")),
+    )
+        .map(|(first, rest): ((Condition<String>, Syntax), Vec<(Condition<String>, Syntax)>)| {
+            let mut branches: Vec<(Condition<String>, Syntax)> = vec![first];
+            branches.extend(rest);
+            Syntax::Piecewise (branches)
+        })
+        .parse(input)
+}
+
+
+/// Parses a branch of a piecewise expression, such as `x if x > 0`, where the condition
+/// compares an expression with zero
+fn branch(input: &Text) -> Result<(Condition<String>, Syntax), ()> {
+    expression
+        .then_ignore(delimited(whitespace(), token("if"), whitespace()))
+        .then(expression)
+        .then_ignore(whitespace().or_not())
+        .then(choice((
+            token(">=").emit(Comparison::NonNegative),
+            token(">").emit(Comparison::Positive),
+            token("!=").emit(Comparison::NonZero),
+        )))
+        .then_ignore(whitespace().or_not().then(token("0")))
+        .map(|((branch, compared), comparison): ((Syntax, Syntax), Comparison)|
+            (Condition::new(comparison, compared), branch)
+        )
+        .parse(input)
+}
+
+
+/// Parses a `Derivative` with respect to arguments at 1-based indices, such as
+/// `∂_1∂_2 f(x, y)`, in any order
+fn partial(input: &Text) -> Result<Syntax, ()> {
+    let ((indices, name), (first, rest)): ((Vec<&str>, &str), (Syntax, Vec<Syntax>)) =
+        repeated_at_least(token("∂_").ignore_then(number()), 1)
+            .then_ignore(whitespace().or_not())
+            .then(unicode_identifier())
+            .then(delimited(
+                token("(").then(whitespace().or_not()),
+                expression.then(repeated(comma.ignore_then(expression))),
+                whitespace().or_not().then(token(")")),
+            ))
+            .parse(input)?;
+    // there is no argument at index 0
+    let mut indices: Vec<usize> = indices.into_iter()
+        .map(|index| usize::from_str(index).ok()?.checked_sub(1))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or(())?;
+    indices.sort_unstable();
+    let mut arguments: Vec<Syntax> = vec![first];
+    arguments.extend(rest);
+    Ok (Syntax::Derivative (name.to_string(), indices, arguments))
+}
+
+
+/// Parses a comma that separates arguments or parameters, with any whitespace around it
+fn comma(input: &Text) -> Result<(), ()> {
+    delimited(whitespace().or_not(), token(","), whitespace().or_not())
+        .emit(())
+        .parse(input)
+}
+
+
 /// Parses an expression enclosed by parentheses
 fn parentheses(input: &Text) -> Result<Syntax, ()> {
     delimited(
@@ -123,4 +268,16 @@ fn parentheses(input: &Text) -> Result<Syntax, ()> {
         whitespace().or_not().then(token(")")),
     )
         .parse(input)
+}
+
+
+/// Parses an absolute value written as an expression enclosed by vertical bars
+fn bars(input: &Text) -> Result<Syntax, ()> {
+    delimited(
+        token("|").then(whitespace().or_not()),
+        expression,
+        whitespace().or_not().then(token("|")),
+    )
+        .map(|term| Syntax::Abs (Box::new(term)))
+        .parse(input)
 }
\ No newline at end of file
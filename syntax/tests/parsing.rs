@@ -0,0 +1,38 @@
+// Copyright Rob Gage 2025
+
+use engine::Expression;
+use num_bigint::BigInt;
+use syntax::parse_expression;
+
+type E = Expression<String>;
+
+fn integer(integer: i64) -> E { E::Integer (BigInt::from(integer)) }
+
+#[test]
+fn minus_signs_bind_more_loosely_than_powers() {
+    // -2^2 = -(2^2)
+    let negation: E = parse_expression("-2^2").unwrap();
+    let power: E = E::Power (Box::new((integer(2), integer(2))));
+    assert!(negation == E::Product (vec![power, integer(-1)]));
+    // negative integers are parenthesized
+    let power: E = E::Power (Box::new((integer(-2), integer(2))));
+    assert!(parse_expression("(-2)^2").unwrap() == power);
+    assert_eq!(power.plain_text().to_string(), "(-2)^2");
+    assert_eq!(integer(-2).plain_text().to_string(), "(-2)");
+    assert!(parse_expression("(-2)").unwrap() == integer(-2));
+}
+
+#[test]
+fn partial_derivatives_are_read_with_sorted_indices() {
+    let variable = |name: &str| E::Variable (name.to_string());
+    let derivative: E = E::Derivative (
+        "f".to_string(),
+        vec![0, 1, 1],
+        vec![variable("x"), variable("y")],
+    );
+    assert!(parse_expression("∂_1∂_2∂_2 f(x, y)").unwrap() == derivative);
+    assert!(parse_expression("∂_2∂_1∂_2 f(x, y)").unwrap() == derivative);
+    assert_eq!(derivative.plain_text().to_string(), "∂_1∂_2∂_2 f(x, y)");
+    // arguments are numbered from 1
+    assert!(parse_expression("∂_0 f(x, y)").is_err());
+}
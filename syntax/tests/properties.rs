@@ -0,0 +1,22 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Generator,
+    Harness,
+};
+use syntax::parse_expression;
+
+#[test]
+fn generated_expressions_have_consistent_derivatives_reductions_and_text() {
+    let variable: String = "x".to_string();
+    let harness: Harness = Harness::new();
+    for seed in 0..20 {
+        let mut generator: Generator<String> = Generator::new(seed, vec![variable.clone()]);
+        if let Err ((expression, failure)) = harness.run(
+            &mut generator,
+            &variable,
+            100,
+            |text| parse_expression(text).ok(),
+        ) { panic!("seed {}: {}\n    {}", seed, expression.plain_text(), failure) }
+    }
+}
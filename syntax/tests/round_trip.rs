@@ -0,0 +1,26 @@
+// Copyright Rob Gage 2025
+
+use engine::{
+    Expression,
+    Generator,
+    Harness,
+};
+use syntax::parse_expression;
+
+#[test]
+fn generated_expressions_are_read_back_from_plain_text() {
+    let harness: Harness = Harness::new();
+    for seed in 0..20 {
+        let mut generator: Generator<String> = Generator::new(
+            seed,
+            vec!["x".to_string(), "y".to_string()],
+        ).functions(vec![("f".to_string(), 1), ("g".to_string(), 2)]);
+        for _ in 0..50 {
+            let expression: Expression<String> = generator.generate();
+            if let Err (failure) = harness.check_round_trip(
+                &expression,
+                |text| parse_expression(text).ok(),
+            ) { panic!("{}", failure) }
+        }
+    }
+}
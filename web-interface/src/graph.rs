@@ -1,6 +1,12 @@
 // Copyright Rob Gage 2025
 
-use engine::Expression;
+use engine::{
+    Bindings,
+    EvaluationError,
+    Expression,
+    Interval,
+    Program,
+};
 use leptos::{
     html::Canvas,
     prelude::*,
@@ -14,6 +20,9 @@ use web_sys::HtmlCanvasElement;
 
 const LINE_VERTEX_COUNT: usize = 500;
 
+/// The number of times intervals between x values may be halved to sample more densely
+const SUBDIVISION_DEPTH: usize = 4;
+
 #[component]
 pub fn Graph(
     formula: Signal<Option<Expression<String>>>,
@@ -21,6 +30,7 @@ pub fn Graph(
 ) -> impl IntoView {
 
     let (scale, set_scale) = signal(0.5);
+    let (error, set_error) = signal(None::<String>);
 
 
     let canvas_reference = NodeRef::<Canvas>::new();
@@ -65,8 +75,30 @@ pub fn Graph(
             for i in 0..LINE_VERTEX_COUNT {
                 x_values.push(minimum_x + (i as f64 * increment))
             }
-            let a_segments: Vec<Vec<(f64, f64)>> = segments(&a, &x_values, minimum_y, maximum_y);
-            let b_segments: Vec<Vec<(f64, f64)>> = segments(&b, &x_values, minimum_y, maximum_y);
+            // only functions of x can be graphed
+            let others: Vec<String> = a.free_variables().into_iter()
+                .filter(|variable| variable != "x")
+                .collect();
+            let (a_segments, b_segments) = match (
+                segments(&a, &x_values, minimum_y, maximum_y),
+                segments(&b, &x_values, minimum_y, maximum_y),
+            ) {
+                _ if !others.is_empty() => {
+                    set_error.set(Some (format!(
+                        "Cannot graph formula: it uses variables other than x ({})",
+                        others.join(", ")
+                    )));
+                    (vec![], vec![])
+                }
+                (Ok (a_segments), Ok (b_segments)) => {
+                    set_error.set(None);
+                    (a_segments, b_segments)
+                }
+                (Err (error), _) | (_, Err (error)) => {
+                    set_error.set(Some (format!("Cannot graph formula: {}", error)));
+                    (vec![], vec![])
+                }
+            };
             for segment in a_segments {
                 chart
                     .draw_series(LineSeries::new(
@@ -121,22 +153,49 @@ pub fn Graph(
                 height="800"
                 style="max-width: 600px; height: auto"
             ></canvas>
+            {move || error.get().map(|error| view! { <p class="graph-error">{error}</p> })}
         </div>
 
     }
 }
 
 /// Computes the line segments of a formula from provided x values
+///
+/// (Intervals between x values are sampled more densely where the formula changes quickly,
+/// and segments are never connected across intervals that may contain poles)
 fn segments(
     formula: &Expression<String>,
     x_values: &[f64],
     minimum_y: f64,
     maximum_y: f64,
-) -> Vec<Vec<(f64, f64)>> {
+) -> Result<Vec<Vec<(f64, f64)>>, EvaluationError<String>> {
+    let variable: String = "x".to_string();
     let mut segments: Vec<Vec<(f64, f64)>> = Vec::new();
     let mut segment: Vec<(f64, f64)> = Vec::new();
-    let Ok (y_values) = formula.evaluate(&"x".to_string(), &x_values) else { return vec![] };
-    for (&x, y) in x_values.into_iter().zip(y_values.into_iter()) {
+    let program: Program = formula.compile(&variable)?;
+    // enclosures are evaluated from bindings shared by every window
+    let bindings: Bindings<String> = formula.eliminate_common_subexpressions();
+    let mut registers: Vec<f64> = vec![0.0; program.instructions().len()];
+    let tolerance: f64 = (maximum_y - minimum_y) / LINE_VERTEX_COUNT as f64;
+    let mut samples: Vec<(f64, bool)> = Vec::with_capacity(x_values.len());
+    if let Some (&first) = x_values.first() { samples.push((first, false)) }
+    for window in x_values.windows(2) {
+        subdivide(
+            &bindings,
+            &variable,
+            window[0],
+            window[1],
+            tolerance,
+            SUBDIVISION_DEPTH,
+            &mut samples
+        );
+    }
+    for (x, connected) in samples {
+        let y: f64 = program.run(&mut registers, x);
+        if !connected && !segment.is_empty() {
+            segments.push(segment);
+            segment = Vec::new();
+        }
         if y.is_nan() || y > maximum_y  || y < minimum_y {
             if !segment.is_empty() {
                 segments.push(segment);
@@ -145,5 +204,29 @@ fn segments(
         } else { segment.push((x, y)); }
     }
     if segment.len() != 0 { segments.push(segment); }
-    segments
-}
\ No newline at end of file
+    Ok (segments)
+}
+
+/// Adds x values to sample between two x values, up to and including the greater one, along
+/// with whether each may be connected to the one before it
+fn subdivide(
+    bindings: &Bindings<String>,
+    variable: &String,
+    minimum_x: f64,
+    maximum_x: f64,
+    tolerance: f64,
+    depth: usize,
+    samples: &mut Vec<(f64, bool)>,
+) {
+    let enclosure: Interval = bindings
+        .evaluate_with(variable, &[Interval::new(minimum_x, maximum_x)])
+        .map_or(Interval::ENTIRE, |enclosures| enclosures[0]);
+    if depth > 0 && !(enclosure.is_bounded() && enclosure.width() <= tolerance) {
+        let middle_x: f64 = (minimum_x + maximum_x) / 2.0;
+        subdivide(bindings, variable, minimum_x, middle_x, tolerance, depth - 1, samples);
+        subdivide(bindings, variable, middle_x, maximum_x, tolerance, depth - 1, samples);
+    } else {
+        // unbounded enclosures may contain poles
+        samples.push((maximum_x, enclosure.is_bounded()));
+    }
+}
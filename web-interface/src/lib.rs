@@ -12,15 +12,8 @@ use leptos::{
     prelude::*,
     mount::mount_to_body,
 };
-use wasm_bindgen::prelude::wasm_bindgen;
 use syntax::parse_expression;
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = katex, js_name = render)]
-    fn katex_render(src: &str, element: web_sys::Element);
-}
-
 #[component]
 pub fn App() -> impl IntoView {
 
@@ -33,10 +26,10 @@ pub fn App() -> impl IntoView {
         move || formula.get().map(|expression| expression.differentiate(&"x".to_string()).reduce())
     );
 
-    let latex = Signal::derive(move || formula.get()
-        .map(|f| format!("{}", f)).unwrap_or("".to_string()));
-    let derived_latex = Signal::derive(move || derivative_formula.get()
-        .map(|f| format!("{}", f)).unwrap_or("".to_string()));
+    let mathml = Signal::derive(move || formula.get()
+        .map(|f| f.mathml().block(true).to_string()).unwrap_or("".to_string()));
+    let derived_mathml = Signal::derive(move || derivative_formula.get()
+        .map(|f| f.mathml().block(true).to_string()).unwrap_or("".to_string()));
     
     view! {
         <div id="container">
@@ -73,11 +66,11 @@ pub fn App() -> impl IntoView {
                 >
                     <div style="margin: 2.5%; width: 45%; height: 200px">
                         <span style="position: absolute; margin: 16px;">f(x) = </span>
-                        <Math latex=latex  />
+                        <Math mathml=mathml />
                     </div>
                     <div style="margin: 2.5%; width: 45%; height: 200px; ">
                         <span style="position: absolute; margin: 16px;">{r"f'(x) = "}</span>
-                        <Math latex=derived_latex />
+                        <Math mathml=derived_mathml />
                     </div>
                 </div>
             </div>
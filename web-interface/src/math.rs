@@ -1,30 +1,12 @@
 // Copyright Rob Gage 2025
 
-// Copyright Rob Gage 2025
-
-use crate::katex_render;
-use leptos::{
-    html::Div,
-    prelude::*
-};
-use wasm_bindgen::prelude::*;
-use web_sys::Element;
+use leptos::prelude::*;
 
 #[component]
 pub fn Math(
-    latex: Signal<String>
+    mathml: Signal<String>
 ) -> impl IntoView {
-
-    let node = NodeRef::<Div>::new();
-
-    Effect::new(move |_| {
-        if let Some(div) = node.get() {
-            let element: Element = div.unchecked_into();
-            katex_render(&latex.get(), element);
-        }
-    });
-
     view! {
-        <div class="math"><div node_ref=node></div></div>
+        <div class="math"><div inner_html=move || mathml.get()></div></div>
     }
-}
\ No newline at end of file
+}